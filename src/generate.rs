@@ -1,9 +1,18 @@
-use std::{collections::HashMap, path::StripPrefixError};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::StripPrefixError,
+};
 
-use crate::{search, ToExternal, ToSmashArc};
+use crate::{path_to_hash, search, Config, ToExternal, ToSmashArc};
 use camino::{FromPathBufError, Utf8Path, Utf8PathBuf};
 use hash40::label_map::LabelMap;
-use smash_arc::{Hash40, LookupError, SearchLookup};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{ser::SerializeMap, Serialize};
+use smash_arc::{
+    ArcLookup, FolderPathListEntry, Hash40, LookupError, PathListEntry, Region, SearchLookup,
+};
 use thiserror::Error;
 
 const INVALID: usize = 0xFF_FFFFusize;
@@ -33,6 +42,101 @@ pub enum GenerateError {
 
     #[error("IO Error")]
     IO(#[from] std::io::Error),
+
+    #[error("No label is known for the hash {0:#x}")]
+    MissingLabel(u64),
+
+    #[error("Invalid ignore pattern")]
+    Ignore(#[from] ignore::Error),
+}
+
+impl GenerateError {
+    /// A stable, machine-readable name for the kind of error
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidFolder => "invalid_folder",
+            Self::InvalidPathIndex => "invalid_path_index",
+            Self::Lookup(_) => "lookup",
+            Self::ConversionError(_) => "conversion",
+            Self::MissingSourceFolder => "missing_source_folder",
+            Self::InvalidRoot(_) => "invalid_root",
+            Self::Search(_) => "search",
+            Self::IO(_) => "io",
+            Self::MissingLabel(_) => "missing_label",
+            Self::Ignore(_) => "ignore",
+        }
+    }
+}
+
+/// Errors are serialized as a map with the following fields:
+/// ```json
+/// {
+///     "kind": "missing_label",
+///     "message": "No label is known for the hash 0x1234567890",
+///     "source": null
+/// }
+/// ```
+/// `kind` is one of the names returned by [`GenerateError::kind`], and `source` is the message of the underlying
+/// error, if there is one.
+impl Serialize for GenerateError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.serialize_entry(
+            "source",
+            &std::error::Error::source(self).map(|source| source.to_string()),
+        )?;
+        map.end()
+    }
+}
+
+/// How the subtrees and files of a walk or a comparison are gone through.
+///
+/// The search section is passed to the mapping function instead of being captured by it, so that only [`Parallel`]
+/// requires the search section to be [`Sync`].
+trait MapStrategy<S>: Copy + Send + Sync {
+    /// Maps every item, the results are always in the same order as the items so that merging them is deterministic
+    fn try_map<T, R, F>(self, search: &S, items: Vec<T>, f: F) -> Result<Vec<R>, GenerateError>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(&S, T) -> Result<R, GenerateError> + Sync + Send;
+}
+
+/// Goes through every item one after the other on the current thread
+#[derive(Clone, Copy)]
+struct Sequential;
+
+impl<S: SearchLookup> MapStrategy<S> for Sequential {
+    fn try_map<T, R, F>(self, search: &S, items: Vec<T>, f: F) -> Result<Vec<R>, GenerateError>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(&S, T) -> Result<R, GenerateError> + Sync + Send,
+    {
+        items.into_iter().map(|item| f(search, item)).collect()
+    }
+}
+
+/// Goes through the items on multiple threads, used by the `_par` functions
+#[cfg(feature = "parallel")]
+#[derive(Clone, Copy)]
+struct Parallel;
+
+#[cfg(feature = "parallel")]
+impl<S: SearchLookup + Sync> MapStrategy<S> for Parallel {
+    fn try_map<T, R, F>(self, search: &S, items: Vec<T>, f: F) -> Result<Vec<R>, GenerateError>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(&S, T) -> Result<R, GenerateError> + Sync + Send,
+    {
+        items.into_par_iter().map(|item| f(search, item)).collect()
+    }
 }
 
 pub enum SearchEntry {
@@ -43,6 +147,37 @@ pub enum SearchEntry {
     },
 }
 
+/// Entries are serialized as maps, with the index into the path list of the search section that was walked:
+/// ```json
+/// { "type": "file", "index": 1024 }
+/// { "type": "folder", "index": 1000, "children": [ ... ] }
+/// ```
+impl Serialize for SearchEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::File(index) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "file")?;
+                map.serialize_entry("index", index)?;
+                map.end()
+            }
+            Self::Folder {
+                path_index,
+                children,
+            } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("type", "folder")?;
+                map.serialize_entry("index", path_index)?;
+                map.serialize_entry("children", children)?;
+                map.end()
+            }
+        }
+    }
+}
+
 pub trait SearchEntryVecExt {
     fn flatten(self) -> Self;
 }
@@ -60,6 +195,32 @@ impl SearchEntryVecExt for Vec<SearchEntry> {
     }
 }
 
+/// Gets the folder entry for a folder, making sure that it is not for a file along the way
+fn get_folder(
+    search: &impl SearchLookup,
+    folder: Hash40,
+) -> Result<&FolderPathListEntry, GenerateError> {
+    if folder == Hash40::from("/") {
+        // skip getting path since it doesn't exist
+        search
+            .get_folder_path_entry_from_hash(folder)
+            .map_err(GenerateError::from)
+    } else {
+        search
+            .get_path_list_entry_from_hash(folder)
+            .map_err(GenerateError::from)
+            .and_then(|path| {
+                if path.is_directory() {
+                    search
+                        .get_folder_path_entry_from_hash(path.path.hash40())
+                        .map_err(GenerateError::from)
+                } else {
+                    Err(GenerateError::InvalidFolder)
+                }
+            })
+    }
+}
+
 /// Performs a walk of the search
 /// ### Arguments
 /// * `folder` - The folder to search (searching "/" will search the root of the filesystem)
@@ -73,6 +234,27 @@ pub fn walk_search_section<H: ToSmashArc>(
     search: &impl SearchLookup,
     folder: H,
     depth: Option<usize>,
+) -> Result<Vec<SearchEntry>, GenerateError> {
+    walk_search_section_impl(search, folder.to_smash_arc(), depth, Sequential)
+}
+
+/// The same as [`walk_search_section`], except that the subtrees are walked on multiple threads.
+///
+/// The children are in the same order as they would be with [`walk_search_section`].
+#[cfg(feature = "parallel")]
+pub fn walk_search_section_par<H: ToSmashArc>(
+    search: &(impl SearchLookup + Sync),
+    folder: H,
+    depth: Option<usize>,
+) -> Result<Vec<SearchEntry>, GenerateError> {
+    walk_search_section_impl(search, folder.to_smash_arc(), depth, Parallel)
+}
+
+fn walk_search_section_impl<S: SearchLookup>(
+    search: &S,
+    folder: Hash40,
+    depth: Option<usize>,
+    strategy: impl MapStrategy<S>,
 ) -> Result<Vec<SearchEntry>, GenerateError> {
     // Begin by checking for the end of our recursive case, which is a 0-depth search
     // A zero depth search should result in no results period.
@@ -80,32 +262,10 @@ pub fn walk_search_section<H: ToSmashArc>(
         return Ok(vec![]);
     }
 
-    let folder = folder.to_smash_arc();
-
-    // Get our base folder, making sure that it is not for a file along the way
-
-    let folder = if folder == Hash40::from("/") {
-        // skip getting path since it doesn't exist
-        search
-            .get_folder_path_entry_from_hash(folder)
-            .map_err(GenerateError::from)?
-    } else {
-        search
-            .get_path_list_entry_from_hash(folder)
-            .map_err(GenerateError::from)
-            .and_then(|path| {
-                if path.is_directory() {
-                    search
-                        .get_folder_path_entry_from_hash(path.path.hash40())
-                        .map_err(GenerateError::from)
-                } else {
-                    Err(GenerateError::InvalidFolder)
-                }
-            })?
-    };
+    let folder = get_folder(search, folder)?;
 
     let mut current_child = folder.get_first_child_index();
-    let mut children = vec![];
+    let mut child_indices = vec![];
 
     // Get our arrays head of time so the code is readable
     let indices = search.get_path_list_indices();
@@ -113,6 +273,7 @@ pub fn walk_search_section<H: ToSmashArc>(
 
     let next_depth = depth.map(|depth| depth - 1);
 
+    // The children are a linked list, so they have to be collected before the subtrees can be walked
     while current_child != INVALID {
         let child_index = indices[current_child] as usize;
 
@@ -120,21 +281,142 @@ pub fn walk_search_section<H: ToSmashArc>(
             return Err(GenerateError::InvalidPathIndex);
         }
 
+        child_indices.push(child_index);
+
+        current_child = paths[child_index].path.index() as usize;
+    }
+
+    strategy.try_map(search, child_indices, |search, child_index| {
         let child = &paths[child_index];
 
         if child.is_directory() {
-            children.push(SearchEntry::Folder {
+            Ok(SearchEntry::Folder {
                 path_index: child_index,
-                children: walk_search_section(search, child.path.hash40(), next_depth)?,
+                children: walk_search_section_impl(
+                    search,
+                    child.path.hash40(),
+                    next_depth,
+                    strategy,
+                )?,
             })
         } else {
-            children.push(SearchEntry::File(child_index));
+            Ok(SearchEntry::File(child_index))
+        }
+    })
+}
+
+/// What a [`SearchVisitor`] wants the walk to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitFlow {
+    /// Keep walking
+    Continue,
+    /// When returned from [`SearchVisitor::enter_folder`], the folder's children are not visited.
+    /// Otherwise, the remaining entries in the same folder are not visited.
+    SkipSubtree,
+    /// End the walk without visiting anything else
+    Stop,
+}
+
+/// Callbacks for the entries of a search section, driven by [`walk_search_section_with`].
+///
+/// Every callback receives the index of the entry in the path list, the entry itself and how deep it is, where the
+/// children of the walked folder have a depth of `0`. All of the callbacks default to continuing the walk.
+///
+/// For example, counting the files with each extension:
+/// ```rs
+/// struct ExtensionCounter(HashMap<Hash40, usize>);
+///
+/// impl SearchVisitor for ExtensionCounter {
+///     fn visit_file(&mut self, _: usize, entry: &PathListEntry, _: usize) -> VisitFlow {
+///         *self.0.entry(entry.ext.hash40()).or_default() += 1;
+///         VisitFlow::Continue
+///     }
+/// }
+/// ```
+pub trait SearchVisitor {
+    /// Called before the children of a folder are visited
+    fn enter_folder(&mut self, _index: usize, _entry: &PathListEntry, _depth: usize) -> VisitFlow {
+        VisitFlow::Continue
+    }
+
+    /// Called for every file
+    fn visit_file(&mut self, _index: usize, _entry: &PathListEntry, _depth: usize) -> VisitFlow {
+        VisitFlow::Continue
+    }
+
+    /// Called after the children of a folder were visited, this is not called if [`enter_folder`](Self::enter_folder)
+    /// skipped the folder
+    fn leave_folder(&mut self, _index: usize, _entry: &PathListEntry, _depth: usize) -> VisitFlow {
+        VisitFlow::Continue
+    }
+}
+
+/// Walks a folder of the search section without collecting the entries, calling the [`SearchVisitor`] for each of them
+/// in the same order as [`walk_search_section`].
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `folder` - The folder to walk (walking "/" will walk the entire search section)
+/// * `visitor` - The visitor to call for every entry
+///
+/// ### Returns
+/// * `Ok(flow)` - [`VisitFlow::Stop`] if the visitor stopped the walk, otherwise [`VisitFlow::Continue`]
+/// * `Err` - A [`GenerateError`]
+pub fn walk_search_section_with<H: ToSmashArc>(
+    search: &impl SearchLookup,
+    folder: H,
+    visitor: &mut impl SearchVisitor,
+) -> Result<VisitFlow, GenerateError> {
+    fn walk(
+        search: &impl SearchLookup,
+        folder: &FolderPathListEntry,
+        visitor: &mut impl SearchVisitor,
+        depth: usize,
+    ) -> Result<VisitFlow, GenerateError> {
+        let indices = search.get_path_list_indices();
+        let paths = search.get_path_list();
+
+        let mut current_child = folder.get_first_child_index();
+        while current_child != INVALID {
+            let child_index = indices[current_child] as usize;
+
+            if child_index == INVALID {
+                return Err(GenerateError::InvalidPathIndex);
+            }
+
+            let child = &paths[child_index];
+
+            let flow = if child.is_directory() {
+                match visitor.enter_folder(child_index, child, depth) {
+                    VisitFlow::Continue => {
+                        let folder = search.get_folder_path_entry_from_hash(child.path.hash40())?;
+                        match walk(search, folder, visitor, depth + 1)? {
+                            VisitFlow::Stop => VisitFlow::Stop,
+                            _ => visitor.leave_folder(child_index, child, depth),
+                        }
+                    }
+                    // skipping a folder only skips its children, not its siblings
+                    VisitFlow::SkipSubtree => VisitFlow::Continue,
+                    VisitFlow::Stop => VisitFlow::Stop,
+                }
+            } else {
+                visitor.visit_file(child_index, child, depth)
+            };
+
+            match flow {
+                VisitFlow::Continue => {}
+                VisitFlow::SkipSubtree => return Ok(VisitFlow::Continue),
+                VisitFlow::Stop => return Ok(VisitFlow::Stop),
+            }
+
+            current_child = child.path.index() as usize;
         }
 
-        current_child = child.path.index() as usize;
+        Ok(VisitFlow::Continue)
     }
 
-    Ok(children)
+    let folder = get_folder(search, folder.to_smash_arc())?;
+    walk(search, folder, visitor, 0)
 }
 
 #[allow(unused)]
@@ -196,11 +478,12 @@ fn get_direct_child<H: ToSmashArc>(
     Ok(None)
 }
 
-fn compare_folders_impl(
-    search: &impl SearchLookup,
+fn compare_folders_impl<S: SearchLookup>(
+    search: &S,
     src: Hash40,
     dst: Hash40,
     parent: search::Folder,
+    strategy: impl MapStrategy<S>,
 ) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
     // first ensure that the source directory exists. If it doesn't exist then we don't
     // know the intended behavior so return an error
@@ -213,17 +496,17 @@ fn compare_folders_impl(
         .ok()
         .map(|index| index as usize);
     // do a 1-depth shallow walk on the source folder
-    let src_entries = walk_search_section(search, src, Some(1))?;
-    let mut missing = HashMap::new();
-    // iterate over each entry and check if the file exists
-    for entry in src_entries {
+    let src_entries = walk_search_section_impl(search, src, Some(1), strategy)?;
+    // check each entry to see if the file exists, the subfolders are compared independently of each other
+    let results = strategy.try_map(search, src_entries, |search, entry| {
+        let mut missing = HashMap::new();
         match entry {
             SearchEntry::File(index) => {
                 // get the path entry
                 let path_entry = &search.get_path_list()[index];
                 // if a file with the same name exists in the destination directory then we just move on
                 if let Some(index) = dst_index && get_direct_child(search, index, path_entry.file_name.hash40())?.is_some() {
-                    continue;
+                    return Ok(missing);
                 }
                 // Otherwise, we are going to insert a new file into our list
                 // of missing files
@@ -259,9 +542,16 @@ fn compare_folders_impl(
                     path_entry.path.hash40(),
                     dst_name.to_smash_arc(),
                     next_folder,
+                    strategy,
                 )?)
             }
         }
+        Ok(missing)
+    })?;
+    // every source path is only visited once, so the results never overlap
+    let mut missing = HashMap::new();
+    for result in results {
+        missing.extend(result);
     }
     Ok(missing)
 }
@@ -295,21 +585,335 @@ pub fn compare_folders(
 ) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
     let src = src.to_smash_arc();
     let dst = dst.to_smash_arc();
+    compare_folders_impl(search, src, dst, root_folder(dst), Sequential)
+}
+
+/// The same as [`compare_folders`], except that the subfolders are compared on multiple threads
+#[cfg(feature = "parallel")]
+pub fn compare_folders_par(
+    search: &(impl SearchLookup + Sync),
+    src: impl ToSmashArc,
+    dst: impl ToSmashArc,
+) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
+    let src = src.to_smash_arc();
+    let dst = dst.to_smash_arc();
+    compare_folders_impl(search, src, dst, root_folder(dst), Parallel)
+}
 
-    let folder = search::Folder {
+/// The folder that the missing files of a comparison are put into
+fn root_folder(dst: Hash40) -> search::Folder {
+    search::Folder {
         full_path: dst.to_external(),
         name: None,
         parent: None,
-    };
-
-    compare_folders_impl(search, src, dst, folder)
+    }
 }
 
+/// The same as [`compare_folders`], except that the destination folder is a folder on the real filesystem.
+///
+/// Every component of the destination path is added to the global label map so that the results can be serialized
+/// with their labels. Use [`compare_folders_path_with_labels`] to provide the label map explicitly.
+///
+/// ### Arguments
+/// - `search` - A reference to an object that implements the search lookups
+/// - `src` - The source folder to compare to
+/// - `dst` - The folder on the filesystem to compare from
+/// - `root` - The root of the filesystem (the mod folder), `dst` must be inside of it
 pub fn compare_folders_path(
     search: &impl SearchLookup,
     src: impl ToSmashArc,
     dst: &Utf8Path,
     root: &Utf8Path,
+) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
+    let map = hash40::Hash40::label_map();
+    let mut labels = map.lock().unwrap();
+    compare_folders_path_with_labels(search, src, dst, root, &mut labels)
+}
+
+/// The same as [`compare_folders_path`], except that the folders which are missing from the filesystem are
+/// compared on multiple threads
+#[cfg(feature = "parallel")]
+pub fn compare_folders_path_par(
+    search: &(impl SearchLookup + Sync),
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
+    let map = hash40::Hash40::label_map();
+    let mut labels = map.lock().unwrap();
+    compare_folders_path_with_labels_par(search, src, dst, root, &mut labels)
+}
+
+/// The same as [`compare_folders_path`], except that the path components are added to the provided label map
+/// instead of the global one.
+///
+/// ### Arguments
+/// - `search` - A reference to an object that implements the search lookups
+/// - `src` - The source folder to compare to
+/// - `dst` - The folder on the filesystem to compare from
+/// - `root` - The root of the filesystem (the mod folder), `dst` must be inside of it
+/// - `labels` - The label map to add the path components to
+pub fn compare_folders_path_with_labels(
+    search: &impl SearchLookup,
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
+    compare_folders_path_with_options(
+        search,
+        src,
+        dst,
+        root,
+        labels,
+        &ComparePathOptions::default(),
+    )
+    .map(|comparison| comparison.missing)
+}
+
+/// The same as [`compare_folders_path_with_labels`], except that the folders which are missing from the filesystem
+/// are compared on multiple threads
+#[cfg(feature = "parallel")]
+pub fn compare_folders_path_with_labels_par(
+    search: &(impl SearchLookup + Sync),
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
+    compare_folders_path_with_options_par(
+        search,
+        src,
+        dst,
+        root,
+        labels,
+        &ComparePathOptions::default(),
+    )
+    .map(|comparison| comparison.missing)
+}
+
+/// Options for comparing a folder in the search section with a folder on the real filesystem
+#[derive(Debug, Clone, Default)]
+pub struct ComparePathOptions {
+    /// Normalizes the names on the filesystem the same way that the game does before hashing them, which means
+    /// lowercasing them and using `/` as the path separator.
+    ///
+    /// Every name which had to be changed is reported as a [`CompareWarning::CaseMismatch`]
+    pub normalize_names: bool,
+
+    /// Files and folders on the filesystem which are not part of the mod, and should be skipped over
+    pub ignore: Option<IgnoreRules>,
+}
+
+/// A set of gitignore-style patterns for entries in a mod folder which should not be treated as game files.
+///
+/// The patterns are matched against paths relative to the root of the mod folder.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    gitignore: Gitignore,
+}
+
+impl IgnoreRules {
+    /// Files which commonly show up in mod folders but are never loaded by the game
+    pub const DEFAULT_PATTERNS: &'static [&'static str] = &[
+        ".git/",
+        ".gitignore",
+        ".gitattributes",
+        ".DS_Store",
+        "Thumbs.db",
+        "desktop.ini",
+        "/info.toml",
+        "/preview.webp",
+        "/config.json",
+        "*.bak",
+        "*.tmp",
+        "*.swp",
+        "*~",
+    ];
+
+    /// Creates a set of rules out of the provided patterns only
+    pub fn new<I, S>(patterns: I) -> Result<Self, GenerateError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in patterns {
+            builder.add_line(None, pattern.as_ref())?;
+        }
+
+        Ok(Self {
+            gitignore: builder.build()?,
+        })
+    }
+
+    /// Creates a set of rules out of the [`DEFAULT_PATTERNS`](Self::DEFAULT_PATTERNS) followed by the provided patterns.
+    ///
+    /// Since the patterns are gitignore-style, a default can be re-included with a negated pattern such as `!/config.json`
+    pub fn with_defaults<I, S>(patterns: I) -> Result<Self, GenerateError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let defaults = Self::DEFAULT_PATTERNS
+            .iter()
+            .map(|pattern| pattern.to_string());
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| pattern.as_ref().to_string());
+        Self::new(defaults.chain(patterns))
+    }
+
+    /// Creates a set of rules out of the [`DEFAULT_PATTERNS`](Self::DEFAULT_PATTERNS) followed by the lines of a gitignore file
+    pub fn from_file<P: AsRef<Utf8Path>>(path: P) -> Result<Self, GenerateError> {
+        let file = std::fs::read_to_string(path.as_ref())?;
+        Self::with_defaults(file.lines())
+    }
+
+    /// Checks if an entry should be ignored
+    /// ### Arguments
+    /// * `path` - The path of the entry, relative to the root of the mod folder
+    /// * `is_dir` - Whether or not the entry is a folder
+    pub fn is_ignored(&self, path: &Utf8Path, is_dir: bool) -> bool {
+        self.gitignore
+            .matched_path_or_any_parents(path.as_std_path(), is_dir)
+            .is_ignore()
+    }
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PATTERNS).expect("The default ignore patterns should be valid")
+    }
+}
+
+/// A non-fatal problem found while comparing a folder on the real filesystem
+///
+/// Warnings are serialized as a map tagged with their `kind`:
+/// ```json
+/// { "kind": "case_mismatch", "path": "mods/mario/Model", "expected": "model" }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompareWarning {
+    /// The name of a file or folder does not match the casing that the game uses
+    CaseMismatch {
+        /// The path of the entry on the filesystem
+        path: Utf8PathBuf,
+        /// The name the game would use for the entry
+        expected: String,
+    },
+}
+
+/// The result of comparing a folder in the search section with a folder on the real filesystem
+///
+/// The comparison is serialized as a map of the missing files, keyed by the path they are missing from, and a list of
+/// [`CompareWarning`]s:
+/// ```json
+/// {
+///     "missing": {
+///         "fighter/mario/model/body/c00/def_mario_001_col.nutexb": "fighter/mario/model/body/c08/def_mario_001_col.nutexb"
+///     },
+///     "warnings": []
+/// }
+/// ```
+#[derive(Debug, Default, Serialize)]
+pub struct PathComparison {
+    /// The files that are missing from the filesystem, keyed by the path of the file they are missing from
+    pub missing: HashMap<hash40::Hash40, search::File>,
+    /// The warnings produced during the comparison
+    pub warnings: Vec<CompareWarning>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.replace('\\', "/").to_lowercase()
+}
+
+/// The same as [`compare_folders_path_with_labels`], with additional control over how the filesystem is read.
+///
+/// ### Arguments
+/// - `search` - A reference to an object that implements the search lookups
+/// - `src` - The source folder to compare to
+/// - `dst` - The folder on the filesystem to compare from
+/// - `root` - The root of the filesystem (the mod folder), `dst` must be inside of it
+/// - `labels` - The label map to add the path components to
+/// - `options` - The [`ComparePathOptions`] to compare with
+pub fn compare_folders_path_with_options(
+    search: &impl SearchLookup,
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+    options: &ComparePathOptions,
+) -> Result<PathComparison, GenerateError> {
+    compare_folders_path_with_options_impl(search, src, dst, root, labels, options, Sequential)
+}
+
+/// The same as [`compare_folders_path_with_options`], except that the folders which are missing from the filesystem
+/// are compared on multiple threads
+#[cfg(feature = "parallel")]
+pub fn compare_folders_path_with_options_par(
+    search: &(impl SearchLookup + Sync),
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+    options: &ComparePathOptions,
+) -> Result<PathComparison, GenerateError> {
+    compare_folders_path_with_options_impl(search, src, dst, root, labels, options, Parallel)
+}
+
+fn compare_folders_path_with_options_impl<S: SearchLookup>(
+    search: &S,
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+    options: &ComparePathOptions,
+    strategy: impl MapStrategy<S>,
+) -> Result<PathComparison, GenerateError> {
+    let mut warnings = vec![];
+
+    // the entries of each folder are checked while walking, but the folders leading up to the destination
+    // are never read so they have to be checked here
+    if options.normalize_names {
+        let mut path = root.to_path_buf();
+        for component in dst.strip_prefix(root)?.components() {
+            path.push(component.as_str());
+            let expected = normalize_name(component.as_str());
+            if expected != component.as_str() {
+                warnings.push(CompareWarning::CaseMismatch {
+                    path: path.as_str().replace('\\', "/").into(),
+                    expected,
+                });
+            }
+        }
+    }
+
+    let missing = compare_folders_path_impl(
+        search,
+        src,
+        dst,
+        root,
+        labels,
+        options,
+        &mut warnings,
+        strategy,
+    )?;
+
+    Ok(PathComparison { missing, warnings })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compare_folders_path_impl<S: SearchLookup>(
+    search: &S,
+    src: impl ToSmashArc,
+    dst: &Utf8Path,
+    root: &Utf8Path,
+    labels: &mut LabelMap,
+    options: &ComparePathOptions,
+    warnings: &mut Vec<CompareWarning>,
+    strategy: impl MapStrategy<S>,
 ) -> Result<HashMap<hash40::Hash40, search::File>, GenerateError> {
     let src = src.to_smash_arc();
 
@@ -318,23 +922,26 @@ pub fn compare_folders_path(
         return Err(GenerateError::MissingSourceFolder);
     }
 
-    let map = hash40::Hash40::label_map();
-    let mut labels = map.lock().unwrap();
-    for component in dst.strip_prefix(root)?.components() {
+    // the path of the destination inside of the game's filesystem
+    let relative: Utf8PathBuf = if options.normalize_names {
+        normalize_name(dst.strip_prefix(root)?.as_str()).into()
+    } else {
+        dst.strip_prefix(root)?.to_path_buf()
+    };
+
+    for component in relative.components() {
         labels.add_labels(vec![component.to_string()]);
     }
-    drop(labels);
-    drop(map);
 
     // check if the destination exists
     if !dst.exists() {
-        let missing_folder_name = dst.strip_prefix(root)?.as_str().to_external();
+        let missing_folder_name = relative.as_str().to_external();
 
         let missing_folder = search::Folder {
             full_path: missing_folder_name,
-            name: Some(dst.file_name().unwrap().to_external()),
+            name: Some(relative.file_name().unwrap().to_external()),
             parent: Some(Box::new(search::Folder::from_path(
-                dst.parent().unwrap().strip_prefix(root)?,
+                relative.parent().unwrap(),
             )?)),
         };
 
@@ -343,11 +950,12 @@ pub fn compare_folders_path(
             src,
             missing_folder_name.to_smash_arc(),
             missing_folder,
+            strategy,
         );
     }
 
     // do a shallow walk on the source path
-    let src_entries = walk_search_section(search, src, Some(1))?;
+    let src_entries = walk_search_section_impl(search, src, Some(1), strategy)?;
 
     // get the entries of the destination folder and create a hashmap of it's entries.
     // unlike the search-only method, we also care about directories because we need to get the path
@@ -361,11 +969,29 @@ pub fn compare_folders_path(
 
             let unix_style: Utf8PathBuf = entry.path().as_str().replace('\\', "/").into();
 
-            entry_hashes.insert(entry.file_name().to_smash_arc(), unix_style);
+            if let Some(rules) = options.ignore.as_ref() {
+                let is_dir = entry.file_type()?.is_dir();
+                if rules.is_ignored(entry.path().strip_prefix(root)?, is_dir) {
+                    continue;
+                }
+            }
+
+            let name = if options.normalize_names {
+                let expected = normalize_name(entry.file_name());
+                if expected != entry.file_name() {
+                    warnings.push(CompareWarning::CaseMismatch {
+                        path: unix_style.clone(),
+                        expected: expected.clone(),
+                    });
+                }
+                expected
+            } else {
+                entry.file_name().to_string()
+            };
+
+            entry_hashes.insert(name.as_str().to_smash_arc(), unix_style);
 
-            let map = hash40::Hash40::label_map();
-            let mut labels = map.lock().unwrap();
-            labels.add_labels(vec![entry.file_name().to_string()]);
+            labels.add_labels(vec![name]);
         }
         entry_hashes
     };
@@ -386,9 +1012,9 @@ pub fn compare_folders_path(
                 missing.insert(
                     path.path.hash40().to_external(),
                     search::File {
-                        full_path: dst.as_str().to_external().join_path(file_name),
+                        full_path: relative.as_str().to_external().join_path(file_name),
                         file_name,
-                        parent: search::Folder::from_path(dst.strip_prefix(root)?)?,
+                        parent: search::Folder::from_path(&relative)?,
                         extension: path.ext.hash40().to_external(),
                     },
                 );
@@ -401,15 +1027,18 @@ pub fn compare_folders_path(
                         return Err(GenerateError::InvalidFolder);
                     }
 
-                    missing.extend(compare_folders_path(
+                    missing.extend(compare_folders_path_impl(
                         search,
                         path.path.hash40(),
                         child_path,
                         root,
+                        labels,
+                        options,
+                        warnings,
+                        strategy,
                     )?);
                 } else {
-                    let missing_folder_name = dst
-                        .strip_prefix(root)?
+                    let missing_folder_name = relative
                         .as_str()
                         .to_external()
                         .join_path(path.file_name.hash40().to_external());
@@ -417,9 +1046,7 @@ pub fn compare_folders_path(
                     let missing_folder = search::Folder {
                         full_path: missing_folder_name,
                         name: Some(path.file_name.hash40().to_external()),
-                        parent: Some(Box::new(search::Folder::from_path(
-                            dst.strip_prefix(root)?,
-                        )?)),
+                        parent: Some(Box::new(search::Folder::from_path(&relative)?)),
                     };
 
                     missing.extend(compare_folders_impl(
@@ -427,6 +1054,7 @@ pub fn compare_folders_path(
                         path.path.hash40(),
                         missing_folder_name.to_smash_arc(),
                         missing_folder,
+                        strategy,
                     )?)
                 }
             }
@@ -443,28 +1071,97 @@ pub fn compare_folders_path(
 /// there are included in the label map.
 ///
 /// This is important for the [`search`] module, as it allows folder paths to construct new hashes based on only file names
+///
+/// ### Returns
+/// * `Ok(stats)` - The [`LabelFillStats`] for every file in the search section
+/// * `Err` - A [`GenerateError`]
 pub fn fill_label_map_from_search(
     search: &impl SearchLookup,
     label_map: &mut LabelMap,
-) -> Result<(), GenerateError> {
-    fn build_new_path(
-        search: &impl SearchLookup,
-        file_index: usize,
-        label_map: &LabelMap,
-    ) -> Option<String> {
-        let path = &search.get_path_list()[file_index];
+) -> Result<LabelFillStats, GenerateError> {
+    fill_label_map_from_subtree(search, "/", label_map)
+}
 
-        // cover degenerate case
-        if let Some(label) = label_map.label_of(path.path.hash40().to_external()) {
-            return Some(label);
-        }
+/// The same as [`fill_label_map_from_search`], except that the files are gone through on multiple threads.
+///
+/// The labels are added in the same order as [`fill_label_map_from_search`], so the results are the same.
+#[cfg(feature = "parallel")]
+pub fn fill_label_map_from_search_par(
+    search: &(impl SearchLookup + Sync),
+    label_map: &mut LabelMap,
+) -> Result<LabelFillStats, GenerateError> {
+    fill_label_map_from_subtree_par(search, "/", label_map)
+}
 
-        let name = label_map.label_of(path.file_name.hash40().to_external())?;
-        let parent = if let Some(parent) = label_map.label_of(path.parent.hash40().to_external()) {
-            parent
-        } else {
-            search
-                .get_path_list_index_from_hash(path.parent.hash40())
+/// Statistics on how well the label map covers the files that were filled by [`fill_label_map_from_search`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LabelFillStats {
+    /// The number of file paths which were already labeled before filling
+    pub already_labeled: usize,
+    /// The number of file paths which were newly labeled from the labels of their parent and file name
+    pub derived: usize,
+    /// The number of file paths which are still missing a label
+    pub unresolved: usize,
+}
+
+impl LabelFillStats {
+    /// The total number of file paths that were visited
+    pub fn total(&self) -> usize {
+        self.already_labeled + self.derived + self.unresolved
+    }
+}
+
+/// The same as [`fill_label_map_from_search`], except that only the files inside of `root` are visited.
+///
+/// This is useful for filling the labels of a single fighter or stage, like `fighter/mario`, without
+/// having to go through the entire search section.
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `root` - The folder to fill the labels of (filling "/" will fill the entire search section)
+/// * `label_map` - The label map to fill
+pub fn fill_label_map_from_subtree(
+    search: &impl SearchLookup,
+    root: impl ToSmashArc,
+    label_map: &mut LabelMap,
+) -> Result<LabelFillStats, GenerateError> {
+    fill_label_map_from_subtree_impl(search, root.to_smash_arc(), label_map, Sequential)
+}
+
+/// The same as [`fill_label_map_from_subtree`], except that the files are gone through on multiple threads
+#[cfg(feature = "parallel")]
+pub fn fill_label_map_from_subtree_par(
+    search: &(impl SearchLookup + Sync),
+    root: impl ToSmashArc,
+    label_map: &mut LabelMap,
+) -> Result<LabelFillStats, GenerateError> {
+    fill_label_map_from_subtree_impl(search, root.to_smash_arc(), label_map, Parallel)
+}
+
+fn fill_label_map_from_subtree_impl<S: SearchLookup>(
+    search: &S,
+    root: Hash40,
+    label_map: &mut LabelMap,
+    strategy: impl MapStrategy<S>,
+) -> Result<LabelFillStats, GenerateError> {
+    fn build_new_path(
+        search: &impl SearchLookup,
+        file_index: usize,
+        label_map: &LabelMap,
+    ) -> Option<String> {
+        let path = &search.get_path_list()[file_index];
+
+        // cover degenerate case
+        if let Some(label) = label_map.label_of(path.path.hash40().to_external()) {
+            return Some(label);
+        }
+
+        let name = label_map.label_of(path.file_name.hash40().to_external())?;
+        let parent = if let Some(parent) = label_map.label_of(path.parent.hash40().to_external()) {
+            parent
+        } else {
+            search
+                .get_path_list_index_from_hash(path.parent.hash40())
                 .ok()
                 .and_then(|index| build_new_path(search, index as usize, label_map))?
         };
@@ -472,37 +1169,1018 @@ pub fn fill_label_map_from_search(
         Some(format!("{}/{}", parent, name))
     }
 
-    let all_files = walk_search_section(search, "/", None).map(SearchEntryVecExt::flatten)?;
+    let all_files =
+        walk_search_section_impl(search, root, None, strategy).map(SearchEntryVecExt::flatten)?;
 
     let paths = search.get_path_list();
 
-    for file in all_files {
-        let SearchEntry::File(index) = file else {
-            unreachable!()
-        };
+    let all_files: Vec<usize> = all_files
+        .into_iter()
+        .map(|file| {
+            let SearchEntry::File(index) = file else {
+                unreachable!()
+            };
+            index
+        })
+        .collect();
+
+    // the labels are only read while going over the files and are inserted afterwards in the same order as the files,
+    // which keeps the results the same regardless of how the files are split up between threads
+    let known = {
+        let label_map: &LabelMap = label_map;
+        strategy.try_map(search, all_files, |_, index| {
+            Ok((
+                index,
+                label_map.label_of(paths[index].path.hash40().to_external()),
+            ))
+        })?
+    };
 
-        let path = &paths[index];
+    let mut stats = LabelFillStats::default();
+    let mut unlabeled = vec![];
 
+    for (index, label) in known {
         // check if the label exists for this string
-        if let Some(label) = label_map.label_of(path.path.hash40().to_external()) {
+        if let Some(label) = label {
+            stats.already_labeled += 1;
             // if it does, we are going to convert it into a path and continually insert all of the components
             // into the label map
             let label_path = Utf8PathBuf::from(label);
             for component in label_path.components() {
                 label_map.add_labels(vec![component.to_string()]);
             }
+        } else {
+            unlabeled.push(index);
+        }
+    }
+
+    // the label does not exist, which means we are going to try recursively constructing the new label passed on the search section hierarchy
+    let derived = {
+        let label_map: &LabelMap = label_map;
+        strategy.try_map(search, unlabeled, |search, index| {
+            Ok(build_new_path(search, index, label_map))
+        })?
+    };
+
+    for label in derived {
+        let Some(label) = label else {
+            stats.unresolved += 1;
+            continue;
+        };
+
+        stats.derived += 1;
+
+        let mut path = Utf8Path::new(label.as_str()).parent();
+        while let Some(parent) = path {
+            path = parent.parent();
+            label_map.add_labels(vec![parent.to_string()]);
+        }
+
+        label_map.add_labels(vec![label]);
+    }
+
+    Ok(stats)
+}
+
+/// Every costume slot name that is checked for when discovering slots, `c00` through `c99`
+pub(crate) fn slot_names() -> impl Iterator<Item = String> {
+    (0..100).map(|slot| format!("c{:02}", slot))
+}
+
+fn find_slot_folders(
+    search: &impl SearchLookup,
+    entries: &[SearchEntry],
+    ancestors: &mut Vec<hash40::Hash40>,
+    owner: &[hash40::Hash40],
+    slot: hash40::Hash40,
+    out: &mut Vec<(usize, Option<hash40::Hash40>)>,
+) {
+    let paths = search.get_path_list();
+
+    for entry in entries {
+        let SearchEntry::Folder { path_index, children } = entry else {
+            continue;
+        };
+
+        let name = paths[*path_index].file_name.hash40().to_external();
+
+        if name != slot {
+            ancestors.push(name);
+            find_slot_folders(search, children, ancestors, owner, slot, out);
+            ancestors.pop();
+            continue;
+        }
+
+        // the slot folder only belongs to us if the owner's components show up somewhere in it's ancestry,
+        // this is what lets us pick up things like `camera/fighter/luigi/c00` for `fighter/luigi`
+        let Some(position) = ancestors
+            .windows(owner.len())
+            .position(|window| window == owner) else {
+            continue;
+        };
+
+        let sub_dir_info = if position > 0 {
+            // outside of the owner's folder, the top level folder names the dir info (camera, movie, etc.)
+            Some(ancestors[0])
+        } else {
+            // inside of the owner's folder, only slots one folder deep get their own dir info (cmn, final, etc.)
+            // anything deeper (model/body, motion/body) is loaded by the slot's dir info itself
+            match &ancestors[owner.len()..] {
+                [category] => Some(*category),
+                _ => None,
+            }
+        };
+
+        out.push((*path_index, sub_dir_info));
+    }
+}
+
+/// Generates the configuration required to add a new costume slot which is based on an existing one.
+///
+/// The dir info hierarchy is discovered from the search section instead of being hard-coded. Every folder named
+/// `base` inside of `owner`, or inside of the same folder in a top level folder (such as `camera/fighter/luigi`), is
+/// considered:
+/// * Folders outside of the owner (such as `camera/fighter/luigi/c00`) are loaded by the sub dir info named after their
+/// top level folder (`fighter/luigi/c08/camera`)
+/// * Folders directly inside of the owner (such as `fighter/luigi/cmn/c00`) are loaded by the sub dir info named after their parent
+/// (`fighter/luigi/c08/cmn`)
+/// * All other folders (such as `fighter/luigi/model/body/c00`) are loaded by the slot's dir info (`fighter/luigi/c08`)
+///
+/// Every file in those folders is shared from the base slot to the new slot, and added to the `new_dir_files` of its dir info.
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let labels = Hash40::label_map();
+/// let config = generate_added_slot(&arc, "fighter/luigi", "c00", "c08", &mut labels.lock().unwrap())?;
+/// ```
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `owner` - The folder which owns the slots, for example `fighter/luigi`
+/// * `base` - The name of the slot to base the new slot on, for example `c00`
+/// * `new` - The name of the slot to add, for example `c08`
+/// * `labels` - The label map used to name the sub dir infos, the new folders are also added to it
+///
+/// ### Returns
+/// * `Ok(config)` - The configuration for the new slot
+/// * `Err` - A [`GenerateError`]
+pub fn generate_added_slot(
+    search: &impl SearchLookup,
+    owner: &str,
+    base: &str,
+    new: &str,
+    labels: &mut LabelMap,
+) -> Result<Config, GenerateError> {
+    let owner = owner.trim_end_matches('/');
+
+    if search
+        .get_path_list_entry_from_hash(owner.to_smash_arc())
+        .is_err()
+    {
+        return Err(GenerateError::MissingSourceFolder);
+    }
+
+    let owner_components: Vec<hash40::Hash40> = Utf8Path::new(owner)
+        .components()
+        .map(|component| component.as_str().to_external())
+        .collect();
+
+    let paths = search.get_path_list();
+
+    // only the owner's folder and the owner's folder inside of each top level folder can have slots that belong to it,
+    // so those are the only ones that get walked
+    let mut roots = vec![(owner.to_external(), vec![])];
+    for entry in walk_search_section(search, "/", Some(1))? {
+        let SearchEntry::Folder { path_index, .. } = entry else {
+            continue;
+        };
+
+        let top = paths[path_index].path.hash40().to_external();
+        let folder = top.join_path(owner.to_external());
+        if search
+            .get_path_list_entry_from_hash(folder.to_smash_arc())
+            .is_ok_and(|entry| entry.is_directory())
+        {
+            roots.push((folder, vec![top]));
+        }
+    }
+
+    let mut slot_folders = vec![];
+    for (folder, mut ancestors) in roots {
+        ancestors.extend(owner_components.iter().copied());
+        let entries = walk_search_section(search, folder, None)?;
+        find_slot_folders(
+            search,
+            &entries,
+            &mut ancestors,
+            &owner_components,
+            base.to_external(),
+            &mut slot_folders,
+        );
+    }
+
+    let slot_dir_info = format!("{}/{}", owner, new);
+    let new_name = new.to_external();
+
+    let mut config = Config::new();
+    let mut dir_infos = BTreeSet::new();
+    dir_infos.insert(slot_dir_info.clone());
+
+    for (index, sub_dir_info) in slot_folders {
+        let path = &paths[index];
+
+        let dir_info = if let Some(sub_dir_info) = sub_dir_info {
+            let name = labels
+                .label_of(sub_dir_info)
+                .ok_or(GenerateError::MissingLabel(sub_dir_info.0))?;
+
+            let dir_info = format!("{}/{}", slot_dir_info, name);
+            config
+                .new_dir_infos_base
+                .insert(dir_info.clone(), format!("{}/{}/{}", owner, base, name));
+            dir_infos.insert(dir_info.clone());
+            dir_info
+        } else {
+            slot_dir_info.clone()
+        };
+
+        // create the new slot folder next to the base one
+        let parent = path.parent.hash40().to_external();
+        let new_folder = parent.join_path(new_name);
+
+        if let Some(parent) = labels.label_of(parent) {
+            labels.add_labels(vec![format!("{}/{}", parent, new)]);
+        }
+
+        let folder = search::Folder {
+            full_path: new_folder,
+            name: Some(new_name),
+            parent: Some(Box::new(search::Folder {
+                full_path: parent,
+                name: None,
+                parent: None,
+            })),
+        };
+
+        let missing = compare_folders_impl(
+            search,
+            path.path.hash40(),
+            new_folder.to_smash_arc(),
+            folder,
+            Sequential,
+        )?;
+
+        let dir_info_files = config
+            .new_dir_files
+            .entry(dir_info.as_str().to_external())
+            .or_insert_with(Vec::new);
+
+        for (source, file) in missing {
+            dir_info_files.push(file.full_path);
+            config
+                .share_to_added
+                .entry(source)
+                .or_insert_with(|| search::FileSet(vec![]))
+                .0
+                .push(file);
+        }
+    }
+
+    // hash map iteration order is random, so sort the files to keep the output stable
+    for files in config.new_dir_files.values_mut() {
+        files.sort_by_key(|hash| hash.0);
+    }
+
+    labels.add_labels(dir_infos.iter().cloned());
+    config.new_dir_infos = dir_infos.into_iter().collect();
+
+    Ok(config)
+}
+
+/// A costume slot folder, such as `fighter/mario/model/body/c00`
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotFolder {
+    /// The name of the slot, such as `c00`
+    pub slot: String,
+    /// The full path of the folder
+    pub path: hash40::Hash40,
+    /// The full path of every file inside of the folder, including the files in its subfolders
+    pub files: Vec<hash40::Hash40>,
+}
+
+/// The costume slot folders which share the same parent folder, such as `fighter/mario/model/body`
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotGroup {
+    /// The full path of the parent folder
+    pub parent: hash40::Hash40,
+    /// The slot folders, in order of their slot
+    pub slots: Vec<SlotFolder>,
+}
+
+/// Every costume slot folder inside of a folder, created with [`list_slots`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlotListing {
+    /// The groups of slot folders, in the order they were found in the search section
+    pub groups: Vec<SlotGroup>,
+}
+
+impl SlotListing {
+    /// Gets the name of every slot that has at least one folder, such as `c00` through `c07` for most fighters
+    pub fn slots(&self) -> BTreeSet<String> {
+        self.groups
+            .iter()
+            .flat_map(|group| group.slots.iter().map(|slot| slot.slot.clone()))
+            .collect()
+    }
+
+    /// Gets every folder of a slot
+    pub fn folders_of<'a>(&'a self, slot: &'a str) -> impl Iterator<Item = &'a SlotFolder> + 'a {
+        self.groups
+            .iter()
+            .flat_map(|group| group.slots.iter())
+            .filter(move |folder| folder.slot == slot)
+    }
+}
+
+fn find_slot_groups(
+    search: &impl SearchLookup,
+    parent: hash40::Hash40,
+    entries: Vec<SearchEntry>,
+    slots: &HashMap<hash40::Hash40, String>,
+    out: &mut Vec<SlotGroup>,
+) {
+    let paths = search.get_path_list();
+    let mut group = SlotGroup {
+        parent,
+        slots: vec![],
+    };
+
+    for entry in entries {
+        let SearchEntry::Folder { path_index, children } = entry else {
+            continue;
+        };
+
+        let path = &paths[path_index];
+
+        // slot folders aren't searched for more slots, everything inside of them belongs to the slot
+        if let Some(slot) = slots.get(&path.file_name.hash40().to_external()) {
+            let mut files: Vec<hash40::Hash40> = children
+                .flatten()
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    SearchEntry::File(index) => Some(paths[index].path.hash40().to_external()),
+                    SearchEntry::Folder { .. } => None,
+                })
+                .collect();
+            files.sort_by_key(|hash| hash.0);
+
+            group.slots.push(SlotFolder {
+                slot: slot.clone(),
+                path: path.path.hash40().to_external(),
+                files,
+            });
+        } else {
+            find_slot_groups(
+                search,
+                path.path.hash40().to_external(),
+                children,
+                slots,
+                out,
+            );
+        }
+    }
+
+    if !group.slots.is_empty() {
+        group.slots.sort_by(|a, b| a.slot.cmp(&b.slot));
+        out.push(group);
+    }
+}
+
+/// Lists every costume slot folder (`c00` through `c99`) inside of a folder, along with the files in each of them.
+///
+/// This is useful for knowing which slots already exist before picking one to add with [`generate_added_slot`].
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let listing = list_slots(&arc, "fighter/mario")?;
+/// for group in listing.groups {
+///     println!("{}: {}", group.parent, group.slots.len());
+/// }
+/// ```
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `owner` - The folder to search for slots, for example `fighter/mario`
+///
+/// ### Returns
+/// * `Ok(listing)` - The slot folders, grouped by their parent folder
+/// * `Err` - A [`GenerateError`]
+pub fn list_slots(search: &impl SearchLookup, owner: &str) -> Result<SlotListing, GenerateError> {
+    let owner = owner.trim_end_matches('/');
+
+    let slots: HashMap<hash40::Hash40, String> = slot_names()
+        .map(|name| (name.as_str().to_external(), name))
+        .collect();
+
+    let entries = walk_search_section(search, owner, None)?;
+
+    let mut listing = SlotListing::default();
+    find_slot_groups(
+        search,
+        owner.to_external(),
+        entries,
+        &slots,
+        &mut listing.groups,
+    );
+
+    Ok(listing)
+}
+
+/// Gets the index of the file data that a file uses in a region. Files which share their data have the same index.
+fn file_data_index(
+    arc: &impl ArcLookup,
+    path: Hash40,
+    region: Region,
+) -> Result<usize, LookupError> {
+    let file_info = arc.get_file_info_from_hash(path)?;
+    Ok(arc.get_file_in_folder(file_info, region).file_data_index.0 as usize)
+}
+
+/// A file replaced by a mod which shares its data with the same file in other costume slots
+#[derive(Debug, Clone, Serialize)]
+pub struct UnshareSuggestion {
+    /// The path of the file in the game's filesystem, such as `fighter/mario/model/body/c00/def_mario_001_col.nutexb`
+    pub path: Utf8PathBuf,
+    /// The slot that the mod replaces the file in, such as `c00`
+    pub slot: String,
+    /// The other slots whose file at the same path shares its data, in order
+    pub shared_with: Vec<String>,
+}
+
+impl UnshareSuggestion {
+    /// The hash of the path, which is what gets added to [`Config::unshare_blacklist`]
+    pub fn hash(&self) -> hash40::Hash40 {
+        path_to_hash(&self.path)
+    }
+}
+
+/// Suggests which files replaced by a mod are candidates for [`Config::unshare_blacklist`].
+///
+/// When a mod replaces a file which is shared between costume slots, ARCropolis unshares it so that only the replaced slot
+/// changes. Redesign mods which are meant to change every slot can keep the file shared by adding it to the blacklist instead.
+///
+/// A replaced file inside of a slot folder (`c00` through `c99`) is suggested when the file at the same path in another slot
+/// uses the same file data. Files which are not in the data.arc are new files, and are never suggested.
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let suggestions = suggest_unshare_blacklist(
+///     &arc,
+///     Region::UsEnglish,
+///     "D:/mods/mario_redesign".into(),
+///     Some(&IgnoreRules::default()),
+/// )?;
+/// for suggestion in suggestions {
+///     println!("{} is shared with {:?}", suggestion.path, suggestion.shared_with);
+/// }
+/// ```
+///
+/// ### Arguments
+/// * `arc` - The data.arc, which is required since the search section does not know which files share data
+/// * `region` - The region to check the file data of
+/// * `root` - The root of the mod folder
+/// * `ignore` - The entries in the mod folder to skip
+///
+/// ### Returns
+/// * `Ok(suggestions)` - The suggested files, sorted by their path
+/// * `Err` - A [`GenerateError`]
+pub fn suggest_unshare_blacklist(
+    arc: &(impl ArcLookup + SearchLookup),
+    region: Region,
+    root: &Utf8Path,
+    ignore: Option<&IgnoreRules>,
+) -> Result<Vec<UnshareSuggestion>, GenerateError> {
+    let slots: BTreeSet<String> = slot_names().collect();
+    let mut suggestions = vec![];
+
+    for path in search::collect_files(root, ignore)? {
+        // the game's paths are always lowercase
+        let path: Utf8PathBuf = normalize_name(path.as_str()).into();
+
+        // only files which replace a vanilla file can be shared
+        let Ok(data) = file_data_index(arc, path_to_hash(&path).to_smash_arc(), region) else {
+            continue;
+        };
+
+        let components: Vec<&str> = path
+            .components()
+            .map(|component| component.as_str())
+            .collect();
+
+        // the closest slot folder to the file is the one it belongs to
+        let Some(position) = components.iter().rposition(|component| slots.contains(*component)) else {
+            continue;
+        };
+
+        let slot = components[position];
+
+        let shared_with: Vec<String> = slots
+            .iter()
+            .filter(|other| other.as_str() != slot)
+            .filter(|other| {
+                let mut other_components = components.clone();
+                other_components[position] = other.as_str();
+                let other_path = Utf8PathBuf::from(other_components.join("/"));
+
+                file_data_index(arc, path_to_hash(&other_path).to_smash_arc(), region)
+                    .is_ok_and(|other_data| other_data == data)
+            })
+            .cloned()
+            .collect();
+
+        if !shared_with.is_empty() {
+            suggestions.push(UnshareSuggestion {
+                slot: slot.to_string(),
+                path,
+                shared_with,
+            });
+        }
+    }
+
+    Ok(suggestions)
+}
+
+/// A pair of files which share the same data, proposed for [`Config::preprocess_reshare`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResharePair {
+    /// The file outside of a costume slot, such as one in a victory screen or result package
+    pub from: hash40::Hash40,
+    /// The file inside of a costume slot that `from` shares its data with
+    pub to: hash40::Hash40,
+    /// The dir infos which load `from`, sorted by their hash
+    pub packages: Vec<hash40::Hash40>,
+}
+
+/// Finds the costume slot (`c00` through `c99`) a path is inside of, by walking up through its parents
+fn slot_of_path(
+    search: &impl SearchLookup,
+    path: Hash40,
+    slots: &HashMap<hash40::Hash40, usize>,
+) -> Option<usize> {
+    let mut current = search.get_path_list_entry_from_hash(path).ok()?;
+    loop {
+        if current.is_directory()
+            && let Some(slot) = slots.get(&current.file_name.hash40().to_external())
+        {
+            return Some(*slot);
+        }
+
+        let parent = current.parent.hash40();
+        if parent == Hash40::from("/") {
+            return None;
+        }
+
+        current = search.get_path_list_entry_from_hash(parent).ok()?;
+    }
+}
+
+/// Proposes [`Config::preprocess_reshare`] entries by finding packages whose files share data with a fighter costume
+/// loaded by another package.
+///
+/// The victory screen of Dark Samus loads a body double of her costume from its own package, and those files have to be reshared
+/// with the costume so that they keep sharing the same data once the costume is modded. Any other package with the same pattern
+/// can be found by looking for files that share their data with a file inside of a costume slot folder, while not being inside of
+/// a slot folder themselves.
+///
+/// When the data is shared by more than one costume file, the file in the lowest slot is proposed.
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let mut config = Config::new();
+/// for pair in find_preprocess_reshare(&arc, Region::UsEnglish)? {
+///     config.preprocess_reshare.insert(pair.from, pair.to);
+/// }
+/// ```
+///
+/// ### Arguments
+/// * `arc` - The data.arc, which is required since the search section does not know which files share data
+/// * `region` - The region to check the file data of
+///
+/// ### Returns
+/// * `Ok(pairs)` - The proposed pairs, sorted by the file being reshared
+/// * `Err` - A [`GenerateError`]
+pub fn find_preprocess_reshare(
+    arc: &(impl ArcLookup + SearchLookup),
+    region: Region,
+) -> Result<Vec<ResharePair>, GenerateError> {
+    let slots: HashMap<hash40::Hash40, usize> = slot_names()
+        .enumerate()
+        .map(|(slot, name)| (name.as_str().to_external(), slot))
+        .collect();
+
+    let file_paths = arc.get_file_paths();
+    let file_infos = arc.get_file_infos();
+
+    // the packages that load each file, and the files that share each piece of data
+    let mut packages: HashMap<Hash40, HashSet<Hash40>> = HashMap::new();
+    let mut shared: HashMap<usize, Vec<Hash40>> = HashMap::new();
+
+    for dir_info in arc.get_dir_infos() {
+        for file_info in &file_infos[dir_info.file_info_range()] {
+            let path = file_paths
+                .get(file_info.file_path_index.0 as usize)
+                .ok_or(GenerateError::InvalidPathIndex)?
+                .path
+                .hash40();
+
+            // a file can be loaded by more than one package, but its data only has to be looked at once
+            let loaded_by = packages.entry(path).or_default();
+            let seen = !loaded_by.is_empty();
+            loaded_by.insert(dir_info.path.hash40());
+            if seen {
+                continue;
+            }
+
+            // a file without data in this region can't share it with anything
+            let Ok(data) = file_data_index(arc, path, region) else {
+                continue;
+            };
+            shared.entry(data).or_default().push(path);
+        }
+    }
+
+    let mut pairs = vec![];
+
+    for paths in shared.into_values().filter(|paths| paths.len() > 1) {
+        let (costumes, others): (Vec<_>, Vec<_>) = paths
+            .into_iter()
+            .map(|path| (path, slot_of_path(arc, path, &slots)))
+            .partition(|(_, slot)| slot.is_some());
+
+        for (from, _) in others {
+            let from_packages = &packages[&from];
+
+            // the costume can't be loaded by any of the same packages, otherwise the files are already loaded together
+            let to = costumes
+                .iter()
+                .filter(|(to, _)| packages[to].is_disjoint(from_packages))
+                .min_by_key(|(to, slot)| (*slot, to.crc32(), to.len()));
+
+            if let Some((to, _)) = to {
+                let mut from_packages: Vec<hash40::Hash40> = from_packages
+                    .iter()
+                    .map(|package| package.to_external())
+                    .collect();
+                from_packages.sort_by_key(|package| package.0);
+
+                pairs.push(ResharePair {
+                    from: from.to_external(),
+                    to: to.to_external(),
+                    packages: from_packages,
+                });
+            }
+        }
+    }
+
+    pairs.sort_by_key(|pair| pair.from.0);
+
+    Ok(pairs)
+}
+
+/// Maps folders to the dir info that loads the files inside of them, which is what [`Config::new_dir_files`] needs
+/// for every file added by a mod.
+///
+/// The mapping is created from the dir info table of the data.arc with [`from_arc`](Self::from_arc), and dir infos which
+/// don't exist in the data.arc (like the ones in [`Config::new_dir_infos`]) can be added with [`insert`](Self::insert).
+#[derive(Debug, Clone, Default)]
+pub struct DirInfoResolver {
+    folders: HashMap<hash40::Hash40, hash40::Hash40>,
+}
+
+impl DirInfoResolver {
+    /// Creates an empty resolver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver from the dir infos of the data.arc.
+    ///
+    /// Every folder is mapped to the dir info which loads the most files directly inside of it.
+    pub fn from_arc(arc: &impl ArcLookup) -> Result<Self, GenerateError> {
+        let file_paths = arc.get_file_paths();
+        let file_infos = arc.get_file_infos();
+
+        let mut counts: HashMap<hash40::Hash40, HashMap<hash40::Hash40, usize>> = HashMap::new();
+
+        for dir_info in arc.get_dir_infos() {
+            let dir_info_path = dir_info.path.hash40().to_external();
+
+            for file_info in &file_infos[dir_info.file_info_range()] {
+                let parent = file_paths
+                    .get(file_info.file_path_index.0 as usize)
+                    .ok_or(GenerateError::InvalidPathIndex)?
+                    .parent
+                    .hash40();
+
+                *counts
+                    .entry(parent.to_external())
+                    .or_default()
+                    .entry(dir_info_path)
+                    .or_default() += 1;
+            }
         }
-        // the label does not exist, which means we are going to try recursively constructing the new label passed on the search section hierarchy
-        else if let Some(label) = build_new_path(search, index, label_map) {
-            let mut path = Utf8Path::new(label.as_str()).parent();
-            while let Some(parent) = path {
-                path = parent.parent();
-                label_map.add_labels(vec![parent.to_string()]);
+
+        let folders = counts
+            .into_iter()
+            .filter_map(|(folder, dir_infos)| {
+                dir_infos
+                    .into_iter()
+                    .max_by(|(a, a_count), (b, b_count)| {
+                        // prefer the lower hash on a tie so that the result doesn't depend on the hash map order
+                        a_count.cmp(b_count).then(b.0.cmp(&a.0))
+                    })
+                    .map(|(dir_info, _)| (folder, dir_info))
+            })
+            .collect();
+
+        Ok(Self { folders })
+    }
+
+    /// Maps the files directly inside of a folder to a dir info, replacing the previous dir info for the folder
+    pub fn insert(&mut self, folder: impl ToExternal, dir_info: impl ToExternal) {
+        self.folders
+            .insert(folder.to_external(), dir_info.to_external());
+    }
+
+    /// Gets the dir info which loads the files directly inside of a folder
+    pub fn get(&self, folder: impl ToExternal) -> Option<hash40::Hash40> {
+        self.folders.get(&folder.to_external()).copied()
+    }
+
+    /// Gets the dir info which should load a file.
+    ///
+    /// If the file's folder is not loaded by any dir info (because it's a new folder for example), the closest parent folder
+    /// which is will be used instead.
+    ///
+    /// ### Arguments
+    /// * `path` - The path of the file in the game's filesystem, components can be hex strings (see [`path_to_hash`])
+    pub fn resolve_file(&self, path: &Utf8Path) -> Option<hash40::Hash40> {
+        path.ancestors()
+            .skip(1)
+            .filter(|folder| !folder.as_str().is_empty())
+            .find_map(|folder| self.get(path_to_hash(folder)))
+    }
+}
+
+/// The [`Config::new_dir_files`] for the files added by a mod, created with [`generate_new_dir_files`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewDirFiles {
+    /// The added files of each dir info
+    pub new_dir_files: HashMap<hash40::Hash40, Vec<hash40::Hash40>>,
+    /// The added files which no dir info could be found for, relative to the root of the mod
+    pub unresolved: Vec<Utf8PathBuf>,
+}
+
+impl NewDirFiles {
+    /// Adds the files to the configuration's `new_dir_files`, skipping the files which are already there
+    pub fn apply(&self, config: &mut Config) {
+        for (dir_info, files) in self.new_dir_files.iter() {
+            let existing = config.new_dir_files.entry(*dir_info).or_default();
+            for file in files {
+                if !existing.contains(file) {
+                    existing.push(*file);
+                }
             }
+        }
+    }
+}
+
+/// Finds the files that a mod adds to the filesystem, and the dir info that has to load each one of them.
+///
+/// Files which already exist in the search section are replacements and are skipped. Every other file is given to the
+/// dir info which loads its folder, see [`DirInfoResolver::resolve_file`].
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let resolver = DirInfoResolver::from_arc(&arc)?;
+/// let files = generate_new_dir_files(&arc, &resolver, "D:/mods/mario_redesign".into(), Some(&IgnoreRules::default()))?;
+/// files.apply(&mut config);
+/// ```
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `resolver` - The folders and the dir infos that load them
+/// * `root` - The root of the mod folder
+/// * `ignore` - The entries in the mod folder to skip
+///
+/// ### Returns
+/// * `Ok(files)` - The added files of each dir info, and the files without a dir info
+/// * `Err` - A [`GenerateError`]
+pub fn generate_new_dir_files(
+    search: &impl SearchLookup,
+    resolver: &DirInfoResolver,
+    root: &Utf8Path,
+    ignore: Option<&IgnoreRules>,
+) -> Result<NewDirFiles, GenerateError> {
+    let paths = search::collect_files(root, ignore)?
+        .into_iter()
+        // the game's paths are always lowercase
+        .map(|path| Utf8PathBuf::from(normalize_name(path.as_str())));
+
+    Ok(resolve_new_files(search, resolver, paths))
+}
+
+/// Gives every file which isn't in the search section to the dir info which should load it
+fn resolve_new_files(
+    search: &impl SearchLookup,
+    resolver: &DirInfoResolver,
+    paths: impl IntoIterator<Item = Utf8PathBuf>,
+) -> NewDirFiles {
+    let mut files = NewDirFiles::default();
+
+    for path in paths {
+        let hash = path_to_hash(&path);
+
+        if search
+            .get_path_list_entry_from_hash(hash.to_smash_arc())
+            .is_ok()
+        {
+            continue;
+        }
+
+        match resolver.resolve_file(&path) {
+            Some(dir_info) => files.new_dir_files.entry(dir_info).or_default().push(hash),
+            None => files.unresolved.push(path),
+        }
+    }
+
+    // hash map iteration order is random, so sort the files to keep the output stable
+    for dir_files in files.new_dir_files.values_mut() {
+        dir_files.sort_by_key(|hash| hash.0);
+    }
+
+    files
+}
+
+/// A costume slot added by a mod, found by [`generate_mod_config`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct AddedSlot {
+    /// The folder which owns the slot, such as `fighter/mario`
+    pub owner: String,
+    /// The name of the slot, such as `c08`
+    pub slot: String,
+}
 
-            label_map.add_labels(vec![label]);
+/// The configuration for a mod folder, created with [`generate_mod_config`]
+#[derive(Serialize)]
+pub struct ModConfig {
+    /// The generated configuration
+    pub config: Config,
+    /// The slots added by the mod, sorted by their owner and name
+    pub added_slots: Vec<AddedSlot>,
+    /// The added files which have to be configured by hand, relative to the root of the mod
+    pub unresolved: Vec<Utf8PathBuf>,
+}
+
+/// Guesses the folder which owns a slot folder from the folders above it, such as `fighter/mario` for both
+/// `fighter/mario/model/body` and `camera/fighter/mario`.
+///
+/// The owner is the first two folders, unless they are inside of another top level folder.
+fn slot_owner(search: &impl SearchLookup, parents: &[&str]) -> Option<String> {
+    if parents.len() >= 3 {
+        let owner = parents[1..3].join("/");
+        if search
+            .get_path_list_entry_from_hash(path_to_hash(Utf8Path::new(&owner)).to_smash_arc())
+            .is_ok_and(|entry| entry.is_directory())
+        {
+            return Some(owner);
+        }
+    }
+
+    (parents.len() >= 2).then(|| parents[..2].join("/"))
+}
+
+/// Generates the configuration for every file in a mod folder.
+///
+/// * Files which replace a vanilla file don't need any configuration, and are skipped
+/// * A slot folder (`c00` through `c99`) which doesn't exist in the search section, next to a `base` slot folder that does, is
+/// an added slot. Every added slot is configured with [`generate_added_slot`]
+/// * Every other file is new, and is given to the dir info which loads its folder (see [`generate_new_dir_files`])
+///
+/// Files in an added slot which the base slot doesn't have, and new files which no dir info was found for, are reported as
+/// unresolved.
+///
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let resolver = DirInfoResolver::from_arc(&arc)?;
+/// let labels = Hash40::label_map();
+/// let generated = generate_mod_config(
+///     &arc,
+///     &resolver,
+///     "D:/mods/mario_c08".into(),
+///     "c00",
+///     Some(&IgnoreRules::default()),
+///     &mut labels.lock().unwrap(),
+/// )?;
+/// ```
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `resolver` - The folders and the dir infos that load them
+/// * `root` - The root of the mod folder
+/// * `base` - The name of the slot to base added slots on, for example `c00`
+/// * `ignore` - The entries in the mod folder to skip
+/// * `labels` - The label map used to name the sub dir infos of added slots, the new folders are also added to it
+///
+/// ### Returns
+/// * `Ok(generated)` - The configuration, the added slots and the files which have to be configured by hand
+/// * `Err` - A [`GenerateError`]
+pub fn generate_mod_config(
+    search: &impl SearchLookup,
+    resolver: &DirInfoResolver,
+    root: &Utf8Path,
+    base: &str,
+    ignore: Option<&IgnoreRules>,
+    labels: &mut LabelMap,
+) -> Result<ModConfig, GenerateError> {
+    let slots: BTreeSet<String> = slot_names().collect();
+    let exists = |components: &[&str]| {
+        search
+            .get_path_list_entry_from_hash(
+                path_to_hash(Utf8Path::new(&components.join("/"))).to_smash_arc(),
+            )
+            .is_ok()
+    };
+
+    let mut added_slots = BTreeSet::new();
+    let mut new_files = vec![];
+    let mut unresolved = vec![];
+
+    for path in search::collect_files(root, ignore)? {
+        // the game's paths are always lowercase
+        let path: Utf8PathBuf = normalize_name(path.as_str()).into();
+        let components: Vec<&str> = path
+            .components()
+            .map(|component| component.as_str())
+            .collect();
+
+        if exists(&components) {
+            continue;
+        }
+
+        // the closest slot folder to the file is the one it belongs to
+        let slot = components[..components.len() - 1]
+            .iter()
+            .rposition(|component| slots.contains(*component))
+            .filter(|position| {
+                let mut base_folder = components[..=*position].to_vec();
+                base_folder[*position] = base;
+                !exists(&components[..=*position]) && exists(&base_folder)
+            });
+
+        let Some(position) = slot else {
+            new_files.push(path);
+            continue;
+        };
+
+        let Some(owner) = slot_owner(search, &components[..position]) else {
+            unresolved.push(path);
+            continue;
+        };
+
+        added_slots.insert(AddedSlot {
+            owner,
+            slot: components[position].to_string(),
+        });
+
+        // only the files that the base slot has are shared to the added slot
+        let mut base_path = components.clone();
+        base_path[position] = base;
+        if !exists(&base_path) {
+            unresolved.push(path);
         }
     }
 
-    Ok(())
+    let mut config = Config::new();
+    for added in added_slots.iter() {
+        config.merge(generate_added_slot(
+            search,
+            &added.owner,
+            base,
+            &added.slot,
+            labels,
+        )?);
+    }
+
+    let files = resolve_new_files(search, resolver, new_files);
+    files.apply(&mut config);
+
+    unresolved.extend(files.unresolved);
+    unresolved.sort();
+
+    Ok(ModConfig {
+        config,
+        added_slots: added_slots.into_iter().collect(),
+        unresolved,
+    })
 }
@@ -0,0 +1,359 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    path::Path,
+};
+
+use hash40::{label_map::LabelMap, Hash40};
+
+use smash_arc::SearchLookup;
+
+use crate::{
+    generate::{slot_names, walk_search_section, GenerateError, SearchEntry},
+    ToExternal, ToSmashArc,
+};
+
+/// Gets the label of a hash, or the hash formatted as a hex string if there is no label for it.
+///
+/// The hex string is in the same format that [`Hash40::from_label`] accepts.
+pub fn label_or_hex(labels: &LabelMap, hash: Hash40) -> String {
+    labels
+        .label_of(hash)
+        .unwrap_or_else(|| format!("0x{:010x}", hash.0))
+}
+
+/// The part of a search section entry that a hash was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnresolvedKind {
+    Path,
+    FileName,
+    Parent,
+    Extension,
+}
+
+impl UnresolvedKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::FileName => "file-name",
+            Self::Parent => "parent",
+            Self::Extension => "extension",
+        }
+    }
+}
+
+/// A hash from the search section which has no label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnresolvedHash {
+    /// The hash which is missing a label
+    pub hash: Hash40,
+    /// Which part of the entry the hash was taken from
+    pub kind: UnresolvedKind,
+    /// The full path of the entry the hash was taken from
+    pub path: Hash40,
+}
+
+/// All of the unresolved hashes which share the same nearest labeled ancestor folder
+#[derive(Debug, Clone)]
+pub struct UnresolvedGroup {
+    /// The hash of the nearest labeled ancestor folder
+    pub ancestor: Hash40,
+    /// The label of the nearest labeled ancestor folder
+    pub label: String,
+    /// The unresolved hashes, each hash only shows up once per kind
+    pub hashes: Vec<UnresolvedHash>,
+}
+
+/// A report of every hash in the search section which has no label, grouped by the nearest labeled ancestor folder
+#[derive(Debug, Clone, Default)]
+pub struct UnresolvedReport {
+    pub groups: Vec<UnresolvedGroup>,
+}
+
+impl UnresolvedReport {
+    /// The total number of unresolved hashes
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|group| group.hashes.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Formats the report as plain text, with one section per group and one hash per line:
+    /// ```text
+    /// [fighter/mario/model]
+    /// path 0x0c3fc6fb4e
+    /// file-name 0x0d7e1b5a62
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for group in self.groups.iter() {
+            let _ = writeln!(text, "[{}]", group.label);
+            for hash in group.hashes.iter() {
+                let _ = writeln!(text, "{} 0x{:010x}", hash.kind.as_str(), hash.hash.0);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Writes the plain text format from [`to_text`](Self::to_text) to a file
+    pub fn write_text<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+}
+
+struct ReportContext<'a> {
+    labels: &'a LabelMap,
+    report: UnresolvedReport,
+    group_lookup: HashMap<Hash40, (usize, HashSet<(UnresolvedKind, Hash40)>)>,
+}
+
+impl<'a> ReportContext<'a> {
+    fn push(&mut self, ancestor: Hash40, kind: UnresolvedKind, hash: Hash40, path: Hash40) {
+        if self.labels.label_of(hash).is_some() {
+            return;
+        }
+
+        if !self.group_lookup.contains_key(&ancestor) {
+            self.report.groups.push(UnresolvedGroup {
+                ancestor,
+                label: label_or_hex(self.labels, ancestor),
+                hashes: vec![],
+            });
+            self.group_lookup
+                .insert(ancestor, (self.report.groups.len() - 1, HashSet::new()));
+        }
+
+        let (index, seen) = self.group_lookup.get_mut(&ancestor).unwrap();
+
+        if seen.insert((kind, hash)) {
+            self.report.groups[*index]
+                .hashes
+                .push(UnresolvedHash { hash, kind, path });
+        }
+    }
+
+    fn collect(&mut self, search: &impl SearchLookup, entries: &[SearchEntry], ancestor: Hash40) {
+        let paths = search.get_path_list();
+
+        for entry in entries {
+            let (index, children) = match entry {
+                SearchEntry::File(index) => (*index, None),
+                SearchEntry::Folder {
+                    path_index,
+                    children,
+                } => (*path_index, Some(children)),
+            };
+
+            let entry = &paths[index];
+            let path = entry.path.hash40().to_external();
+
+            self.push(ancestor, UnresolvedKind::Path, path, path);
+            self.push(
+                ancestor,
+                UnresolvedKind::FileName,
+                entry.file_name.hash40().to_external(),
+                path,
+            );
+            self.push(
+                ancestor,
+                UnresolvedKind::Parent,
+                entry.parent.hash40().to_external(),
+                path,
+            );
+
+            // folders don't have extensions
+            if !entry.is_directory() {
+                self.push(
+                    ancestor,
+                    UnresolvedKind::Extension,
+                    entry.ext.hash40().to_external(),
+                    path,
+                );
+            }
+
+            if let Some(children) = children {
+                let ancestor = if self.labels.label_of(path).is_some() {
+                    path
+                } else {
+                    ancestor
+                };
+                self.collect(search, children, ancestor);
+            }
+        }
+    }
+}
+
+/// Finds every path, file name, parent and extension hash in a folder of the search section which has no label.
+///
+/// This is intended to be used after the label map has been filled with [`fill_label_map_from_search`](crate::generate::fill_label_map_from_search),
+/// so that the only hashes left are the ones which cannot be derived from any known label.
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `root` - The folder to search (searching "/" will search the entire search section)
+/// * `labels` - The label map to check the hashes against
+pub fn find_unresolved(
+    search: &impl SearchLookup,
+    root: impl ToSmashArc,
+    labels: &LabelMap,
+) -> Result<UnresolvedReport, GenerateError> {
+    let root = root.to_smash_arc();
+    let entries = walk_search_section(search, root, None)?;
+
+    let mut context = ReportContext {
+        labels,
+        report: UnresolvedReport::default(),
+        group_lookup: HashMap::new(),
+    };
+
+    context.collect(search, &entries, root.to_external());
+
+    Ok(context.report)
+}
+
+/// The names to try when cracking unresolved hashes with [`crack_unresolved`]
+#[derive(Debug, Clone, Default)]
+pub struct CandidateNames {
+    /// Names to try, such as the lines of a wordlist. For files these are tried both as they are, and with each extension appended
+    pub words: Vec<String>,
+    /// Whether or not to try the costume slot names `c00` through `c99`
+    pub slots: bool,
+    /// Extensions to try in addition to the labeled extensions found in the search section, without the leading `.`
+    pub extensions: Vec<String>,
+}
+
+impl CandidateNames {
+    /// Creates a set of candidates out of a wordlist file with one name per line
+    pub fn from_wordlist<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let words = std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+
+        Ok(Self {
+            words,
+            slots: false,
+            extensions: vec![],
+        })
+    }
+}
+
+/// A label that was found by [`crack_unresolved`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrackedLabel {
+    /// The full path hash that was cracked
+    pub hash: Hash40,
+    /// The new label of the full path
+    pub label: String,
+}
+
+/// Attempts to find the labels of the unresolved paths in a report by trying candidate names under their parent.
+///
+/// Since the parent of every path is known from the search section, only the name has to be guessed. Each candidate
+/// is joined onto the labeled parent with [`Hash40::join_path`] and checked against the path's hash, so paths whose
+/// parent is also unresolved are skipped. Every label that is found is added to the label map.
+///
+/// Folder labels that are found can make more paths resolvable, so it is worth running
+/// [`fill_label_map_from_search`](crate::generate::fill_label_map_from_search) and [`find_unresolved`] again afterwards.
+///
+/// ### Arguments
+/// * `search` - The search section the report was made from
+/// * `report` - The report of unresolved hashes
+/// * `candidates` - The names to try
+/// * `labels` - The label map to read parents from and add found labels to
+pub fn crack_unresolved(
+    search: &impl SearchLookup,
+    report: &UnresolvedReport,
+    candidates: &CandidateNames,
+    labels: &mut LabelMap,
+) -> Vec<CrackedLabel> {
+    let mut names: Vec<String> = candidates.words.clone();
+    if candidates.slots {
+        names.extend(slot_names());
+    }
+
+    // every extension that is known in the search section is a good guess for files
+    let mut extensions: Vec<String> = candidates.extensions.clone();
+    let mut seen_extensions: HashSet<Hash40> = extensions
+        .iter()
+        .map(|extension| Hash40::new(extension))
+        .collect();
+    for path in search.get_path_list() {
+        let extension = path.ext.hash40().to_external();
+        if path.is_directory() || !seen_extensions.insert(extension) {
+            continue;
+        }
+
+        if let Some(label) = labels.label_of(extension) {
+            extensions.push(label);
+        }
+    }
+
+    let mut cracked = vec![];
+
+    let unresolved_paths = report
+        .groups
+        .iter()
+        .flat_map(|group| group.hashes.iter())
+        .filter(|hash| hash.kind == UnresolvedKind::Path);
+
+    for unresolved in unresolved_paths {
+        let Ok(entry) = search.get_path_list_entry_from_hash(unresolved.hash.to_smash_arc()) else {
+            continue;
+        };
+
+        let parent = entry.parent.hash40().to_external();
+        let Some(parent_label) = labels.label_of(parent) else {
+            continue;
+        };
+
+        let is_match = |name: &str| parent.join_path(Hash40::new(name)) == unresolved.hash;
+
+        let found = if entry.is_directory() {
+            names.iter().find(|name| is_match(name)).cloned()
+        } else {
+            // if the extension is already known, then there is no reason to try the rest of them
+            let extension = entry.ext.hash40().to_external();
+            let extensions = match labels.label_of(extension) {
+                Some(label) => vec![label],
+                None => extensions.clone(),
+            };
+
+            names.iter().find_map(|name| {
+                if is_match(name) {
+                    return Some(name.clone());
+                }
+
+                extensions
+                    .iter()
+                    .map(|extension| format!("{}.{}", name, extension))
+                    .find(|name| is_match(name))
+            })
+        };
+
+        let Some(name) = found else {
+            continue;
+        };
+
+        let label = format!("{}/{}", parent_label, name);
+
+        labels.add_labels(vec![name.clone(), label.clone()]);
+
+        // the extension might not have been known before either
+        if let Some((_, extension)) = name.rsplit_once('.') {
+            labels.add_labels(vec![extension.to_string()]);
+        }
+
+        cracked.push(CrackedLabel {
+            hash: unresolved.hash,
+            label,
+        });
+    }
+
+    cracked
+}
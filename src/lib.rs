@@ -10,8 +10,12 @@ pub use hash40;
 
 pub mod generate;
 
+pub mod labels;
+
 pub mod search;
 
+pub mod validate;
+
 pub use smash_arc;
 
 /// The base ARCropolis mod configuration format.
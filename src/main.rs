@@ -0,0 +1,450 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use arc_config::{
+    generate::{self, DirInfoResolver, GenerateError, IgnoreRules, SearchEntry},
+    hash40::{label_map::LabelMap, Hash40},
+    labels::{find_unresolved, label_or_hex},
+    search::{self, SearchError, UserSearchSection},
+    smash_arc::{self, ArcFile, SearchLookup},
+    validate, Config, ToExternal,
+};
+use camino::Utf8PathBuf;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+
+/// Tools for creating and checking ARCropolis mod configurations
+#[derive(Parser)]
+#[clap(name = "arc-config", version)]
+struct Args {
+    /// A file of labels to load before running the command, with one label per line
+    #[clap(long, global = true)]
+    labels: Option<PathBuf>,
+
+    /// Print results as JSON instead of text, using the serialized form of each result type
+    #[clap(long, global = true)]
+    json: bool,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks that every vanilla file a configuration refers to exists
+    Validate {
+        /// The search section: a data.arc, a binary search section, or a path list
+        #[clap(long)]
+        source: PathBuf,
+
+        /// The configuration to check
+        config: PathBuf,
+    },
+
+    /// Merges configurations together, in order
+    Merge {
+        /// The file to write the merged configuration to
+        #[clap(long, short)]
+        output: PathBuf,
+
+        /// The configurations to merge
+        #[clap(required = true)]
+        configs: Vec<PathBuf>,
+    },
+
+    /// Lists the files that were added, removed, or moved between two search sections
+    Diff {
+        /// The old search section: a data.arc, a binary search section, or a path list
+        old: PathBuf,
+
+        /// The new search section: a data.arc, a binary search section, or a path list
+        new: PathBuf,
+    },
+
+    /// Generates the configuration for a mod folder.
+    ///
+    /// Slot folders which don't exist in the search section, such as fighter/mario/model/body/c08, are configured as added
+    /// slots based on `--base`. Every other file which doesn't replace a vanilla file is added to `new-dir-files`, which
+    /// needs the dir infos of a data.arc as the source.
+    Generate {
+        /// The search section: a data.arc, a binary search section, or a path list
+        #[clap(long)]
+        source: PathBuf,
+
+        /// The root of the mod folder
+        #[clap(long)]
+        root: Utf8PathBuf,
+
+        /// The slot to base added slots on
+        #[clap(long, default_value = "c00")]
+        base: String,
+
+        /// A gitignore-style file of entries in the mod folder to skip, in addition to the defaults
+        #[clap(long)]
+        ignore: Option<Utf8PathBuf>,
+
+        /// The file to write the configuration to, otherwise it is printed
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Rewrites a configuration in the standard format
+    Normalize {
+        /// The configuration to normalize
+        config: PathBuf,
+
+        /// The file to write the configuration to, otherwise the configuration is overwritten
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Prints a folder of the search section
+    Walk {
+        /// The search section: a data.arc, a binary search section, or a path list
+        #[clap(long)]
+        source: PathBuf,
+
+        /// The folder to print
+        #[clap(default_value = "/")]
+        folder: String,
+
+        /// How many folders deep to print
+        #[clap(long)]
+        depth: Option<usize>,
+    },
+
+    /// Fills the label map from the search section and exports it
+    Labels {
+        /// The search section: a data.arc, a binary search section, or a path list
+        #[clap(long)]
+        source: PathBuf,
+
+        /// The file to write every labeled path to
+        #[clap(long, short)]
+        output: PathBuf,
+
+        /// The file to write the report of unresolved hashes to
+        #[clap(long)]
+        unresolved: Option<PathBuf>,
+    },
+}
+
+/// Any kind of search section that can be provided on the command line
+enum Source {
+    Arc(ArcFile),
+    User(UserSearchSection),
+}
+
+impl Source {
+    /// Opens a data.arc if the file has the `arc` extension, otherwise a binary search section or a path list
+    fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("arc"))
+            .unwrap_or(false)
+        {
+            return Ok(Self::Arc(ArcFile::open(path)?));
+        }
+
+        match UserSearchSection::from_file_binary(path) {
+            Ok(section) => Ok(Self::User(section)),
+            Err(SearchError::InvalidBinary) => Ok(Self::User(UserSearchSection::from_path(path)?)),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl SearchLookup for Source {
+    fn get_folder_path_to_index(&self) -> &[smash_arc::HashToIndex] {
+        match self {
+            Self::Arc(arc) => arc.get_folder_path_to_index(),
+            Self::User(user) => user.get_folder_path_to_index(),
+        }
+    }
+
+    fn get_folder_path_list(&self) -> &[smash_arc::FolderPathListEntry] {
+        match self {
+            Self::Arc(arc) => arc.get_folder_path_list(),
+            Self::User(user) => user.get_folder_path_list(),
+        }
+    }
+
+    fn get_path_to_index(&self) -> &[smash_arc::HashToIndex] {
+        match self {
+            Self::Arc(arc) => arc.get_path_to_index(),
+            Self::User(user) => user.get_path_to_index(),
+        }
+    }
+
+    fn get_path_list_indices(&self) -> &[u32] {
+        match self {
+            Self::Arc(arc) => arc.get_path_list_indices(),
+            Self::User(user) => user.get_path_list_indices(),
+        }
+    }
+
+    fn get_path_list(&self) -> &[smash_arc::PathListEntry] {
+        match self {
+            Self::Arc(arc) => arc.get_path_list(),
+            Self::User(user) => user.get_path_list(),
+        }
+    }
+}
+
+/// Opens a search section and fills the global label map from it
+fn open_labeled(path: &Path) -> Result<Source, Box<dyn Error>> {
+    let source = Source::open(path)?;
+    let labels = Hash40::label_map();
+    let mut labels = labels.lock().unwrap();
+    generate::fill_label_map_from_search(&source, &mut labels)?;
+    Ok(source)
+}
+
+fn write_or_print(config: &Config, output: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    match output {
+        Some(output) => config.to_file_json(output)?,
+        None => println!("{}", serde_json::to_string_pretty(config)?),
+    }
+    Ok(())
+}
+
+fn print_entries(
+    search: &impl SearchLookup,
+    entries: &[SearchEntry],
+    labels: &LabelMap,
+    depth: usize,
+) {
+    let paths = search.get_path_list();
+    for entry in entries {
+        match entry {
+            SearchEntry::File(index) => {
+                let name = paths[*index].file_name.hash40().to_external();
+                println!("{}{}", "  ".repeat(depth), label_or_hex(labels, name));
+            }
+            SearchEntry::Folder {
+                path_index,
+                children,
+            } => {
+                let name = paths[*path_index].file_name.hash40().to_external();
+                println!("{}{}/", "  ".repeat(depth), label_or_hex(labels, name));
+                print_entries(search, children, labels, depth + 1);
+            }
+        }
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn run(args: Args) -> Result<bool, Box<dyn Error>> {
+    if let Some(path) = args.labels.as_ref() {
+        let file = std::fs::read_to_string(path)?;
+        let labels = Hash40::label_map();
+        let mut labels = labels.lock().unwrap();
+        labels.add_labels(
+            file.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from),
+        );
+    }
+
+    match args.command {
+        Command::Validate { source, config } => {
+            let source = open_labeled(&source)?;
+            let config = Config::from_file_json(config)?;
+
+            let issues = validate::validate(&source, &config);
+
+            if args.json {
+                print_json(&issues)?;
+                return Ok(issues.is_empty());
+            }
+
+            let labels = Hash40::label_map();
+            let labels = labels.lock().unwrap();
+            for issue in issues.iter() {
+                let problem = match issue {
+                    validate::ValidationIssue::MissingFile { .. } => "does not exist",
+                    validate::ValidationIssue::NotAFile { .. } => "is a folder",
+                };
+                println!(
+                    "{:?}: {} {}",
+                    issue.field(),
+                    label_or_hex(&labels, issue.path()),
+                    problem
+                );
+            }
+
+            Ok(issues.is_empty())
+        }
+        Command::Merge { output, configs } => {
+            let mut merged = Config::new();
+            for config in configs {
+                merged.merge(Config::from_file_json(config)?);
+            }
+            merged.to_file_json(output)?;
+            Ok(true)
+        }
+        Command::Diff { old, new } => {
+            let old = open_labeled(&old)?;
+            let new = open_labeled(&new)?;
+
+            let diff = search::diff(&old, &new);
+
+            if args.json {
+                print_json(&diff)?;
+                return Ok(true);
+            }
+
+            let labels = Hash40::label_map();
+            let labels = labels.lock().unwrap();
+            print!("{}", diff.to_text(&labels));
+
+            Ok(true)
+        }
+        Command::Generate {
+            source,
+            root,
+            base,
+            ignore,
+            output,
+        } => {
+            let source = open_labeled(&source)?;
+
+            let ignore = match ignore {
+                Some(path) => IgnoreRules::from_file(path)?,
+                None => IgnoreRules::default(),
+            };
+
+            // only the data.arc has dir infos, so new files can't be resolved with any other source
+            let resolver = match &source {
+                Source::Arc(arc) => DirInfoResolver::from_arc(arc)?,
+                Source::User(_) => DirInfoResolver::new(),
+            };
+
+            // the label map can't be locked while the config is serialized, since serializing uses it as well
+            let generated = {
+                let labels = Hash40::label_map();
+                let mut labels = labels.lock().unwrap();
+                generate::generate_mod_config(
+                    &source,
+                    &resolver,
+                    &root,
+                    &base,
+                    Some(&ignore),
+                    &mut labels,
+                )?
+            };
+
+            if args.json {
+                match output {
+                    Some(output) => {
+                        generated.config.to_file_json(output)?;
+                        print_json(&json!({
+                            "added_slots": generated.added_slots,
+                            "unresolved": generated.unresolved,
+                        }))?;
+                    }
+                    None => print_json(&generated)?,
+                }
+            } else {
+                for added in generated.added_slots.iter() {
+                    eprintln!("added slot {} for {}", added.slot, added.owner);
+                }
+                for path in generated.unresolved.iter() {
+                    eprintln!("warning: {} has to be configured by hand", path);
+                }
+                write_or_print(&generated.config, output.as_deref())?;
+            }
+            Ok(true)
+        }
+        Command::Normalize { config, output } => {
+            let parsed = Config::from_file_json(&config)?;
+            parsed.to_file_json(output.unwrap_or(config))?;
+            Ok(true)
+        }
+        Command::Walk {
+            source,
+            folder,
+            depth,
+        } => {
+            let source = open_labeled(&source)?;
+            let entries = generate::walk_search_section(&source, folder.as_str(), depth)?;
+
+            if args.json {
+                print_json(&entries)?;
+                return Ok(true);
+            }
+
+            let labels = Hash40::label_map();
+            let labels = labels.lock().unwrap();
+            print_entries(&source, &entries, &labels, 0);
+
+            Ok(true)
+        }
+        Command::Labels {
+            source,
+            output,
+            unresolved,
+        } => {
+            let source = Source::open(&source)?;
+
+            let labels = Hash40::label_map();
+            let mut labels = labels.lock().unwrap();
+            let stats = generate::fill_label_map_from_search(&source, &mut labels)?;
+
+            if args.json {
+                // the stats contain no hashes, so they can be serialized while the label map is locked
+                print_json(&stats)?;
+            } else {
+                eprintln!(
+                    "{} already labeled, {} derived, {} unresolved",
+                    stats.already_labeled, stats.derived, stats.unresolved
+                );
+            }
+
+            let mut paths: Vec<String> = source
+                .get_path_list()
+                .iter()
+                .filter_map(|path| labels.label_of(path.path.hash40().to_external()))
+                .collect();
+            paths.sort();
+            paths.dedup();
+            std::fs::write(output, paths.join("\n") + "\n")?;
+
+            if let Some(unresolved) = unresolved {
+                find_unresolved(&source, "/", &labels)?.write_text(unresolved)?;
+            }
+
+            Ok(true)
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let json = args.json;
+    match run(args) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            if json {
+                // errors from the library have a stable form, anything else is reported by its message
+                let error = match e.downcast_ref::<GenerateError>() {
+                    Some(e) => serde_json::to_value(e).unwrap_or_default(),
+                    None => json!({ "kind": "other", "message": e.to_string(), "source": null }),
+                };
+                println!("{}", json!({ "error": error }));
+            } else {
+                eprintln!("error: {}", e);
+            }
+            std::process::exit(1);
+        }
+    }
+}
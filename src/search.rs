@@ -1,11 +1,22 @@
-use camino::Utf8Path;
-use hash40::Hash40;
+use camino::{Utf8Path, Utf8PathBuf};
+use hash40::{label_map::LabelMap, Hash40};
 use serde::{de::Error, de::Visitor, ser::SerializeMap, Deserialize, Serialize};
 use smash_arc::{FolderPathListEntry, PathListEntry, SearchListEntry, SearchLookup};
-use std::{collections::BTreeMap, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+    path::Path,
+};
 use thiserror::Error;
 
-use crate::path_to_hash;
+use crate::{
+    generate::{
+        walk_search_section, walk_search_section_with, GenerateError, IgnoreRules, SearchEntry,
+        SearchVisitor, VisitFlow,
+    },
+    labels::label_or_hex,
+    path_to_hash, Config, ToExternal, ToSmashArc,
+};
 
 #[derive(Error, Debug)]
 pub enum SearchError {
@@ -22,6 +33,22 @@ pub enum SearchError {
     #[error("The file path is missing an extension")]
     MissingExtension,
 
+    /// The path provided does not exist in the search section
+    #[error("The path does not exist in the search section")]
+    MissingPath,
+
+    /// The path provided already exists in the search section
+    #[error("The path already exists in the search section")]
+    AlreadyExists,
+
+    /// The binary search section is either truncated or not a search section at all
+    #[error("The binary search section is invalid")]
+    InvalidBinary,
+
+    /// The binary search section was written by an incompatible version of this library
+    #[error("The binary search section version {0} is not supported")]
+    UnsupportedVersion(u32),
+
     /// Other generic IO error
     #[error("IO Error")]
     IO(#[from] std::io::Error),
@@ -478,6 +505,209 @@ impl Folder {
     }
 }
 
+fn make_hash_to_index(hash: Hash40) -> smash_arc::HashToIndex {
+    let mut value = smash_arc::HashToIndex::default();
+    value.set_hash(hash.crc());
+    value.set_length(hash.str_len());
+    value
+}
+
+fn make_path_entry(path: &Utf8Path) -> Result<PathListEntry, SearchError> {
+    let file_name = path.file_name().ok_or(SearchError::MissingFileName)?;
+
+    let (file_name, extension) = if file_name.starts_with("0x") {
+        let mut split = file_name.split('.');
+        (
+            split.next().ok_or(SearchError::MissingFileName)?,
+            split.next().ok_or(SearchError::MissingExtension)?,
+        )
+    } else {
+        let extension = path.extension().ok_or(SearchError::MissingExtension)?;
+        (file_name, extension)
+    };
+
+    let parent = path.parent().ok_or(SearchError::InvalidParent)?;
+
+    let full_path = path_to_hash(path);
+    let name = Hash40::from_label(file_name).unwrap();
+    let extension = Hash40::from_label(extension).unwrap();
+    let parent_hash = path_to_hash(parent);
+
+    let mut path = smash_arc::HashToIndex::default();
+    let mut file_name = smash_arc::HashToIndex::default();
+    let mut parent = smash_arc::HashToIndex::default();
+    let mut ext = smash_arc::HashToIndex::default();
+
+    path.set_hash(full_path.crc());
+    path.set_length(full_path.str_len());
+    path.set_index(0xFF_FFFFu32);
+
+    file_name.set_hash(name.crc());
+    file_name.set_length(name.str_len());
+
+    parent.set_hash(parent_hash.crc());
+    parent.set_length(parent_hash.str_len());
+
+    ext.set_hash(extension.crc());
+    ext.set_length(extension.str_len());
+
+    Ok(PathListEntry(SearchListEntry {
+        path,
+        file_name,
+        parent,
+        ext,
+    }))
+}
+
+fn make_folder_entry(path: &Utf8Path) -> Result<FolderPathListEntry, SearchError> {
+    let file_name = path.file_name().ok_or(SearchError::MissingFileName)?;
+    let parent = path.parent().ok_or(SearchError::InvalidParent)?;
+
+    let full_path = path_to_hash(path);
+    let name = Hash40::from_label(file_name).unwrap();
+    let parent_hash = if parent == Utf8Path::new("") {
+        Hash40::new("/")
+    } else {
+        path_to_hash(parent)
+    };
+
+    let mut path = smash_arc::HashToIndex::default();
+    let mut file_name = smash_arc::HashToIndex::default();
+    let mut parent = smash_arc::HashToIndex::default();
+    let ext = smash_arc::HashToIndex::default();
+
+    path.set_hash(full_path.crc());
+    path.set_length(full_path.str_len());
+
+    file_name.set_hash(name.crc());
+    file_name.set_length(name.str_len());
+
+    parent.set_hash(parent_hash.crc());
+    parent.set_length(parent_hash.str_len());
+
+    Ok(FolderPathListEntry(SearchListEntry {
+        path,
+        file_name,
+        parent,
+        ext,
+    }))
+}
+
+fn make_root_folder() -> FolderPathListEntry {
+    let path = make_hash_to_index(Hash40::new("/"));
+
+    let mut root = FolderPathListEntry(SearchListEntry {
+        path,
+        file_name: path,
+        parent: smash_arc::HashToIndex::default(),
+        ext: smash_arc::HashToIndex::default(),
+    });
+
+    root.set_first_child_index(0xFF_FFFF);
+    root
+}
+
+fn lookup_find(lookup: &[smash_arc::HashToIndex], hash: smash_arc::Hash40) -> Option<usize> {
+    lookup
+        .binary_search_by_key(&hash, |key| key.hash40())
+        .ok()
+        .map(|position| lookup[position].index() as usize)
+}
+
+fn lookup_insert(lookup: &mut Vec<smash_arc::HashToIndex>, hash: smash_arc::Hash40, index: usize) {
+    let mut key = smash_arc::HashToIndex::default();
+    key.set_hash(hash.crc32());
+    key.set_length(hash.len());
+    key.set_index(index as u32);
+
+    match lookup.binary_search_by_key(&hash, |key| key.hash40()) {
+        Ok(position) => lookup[position] = key,
+        Err(position) => lookup.insert(position, key),
+    }
+}
+
+fn lookup_remove(lookup: &mut Vec<smash_arc::HashToIndex>, hash: smash_arc::Hash40) {
+    if let Ok(position) = lookup.binary_search_by_key(&hash, |key| key.hash40()) {
+        lookup.remove(position);
+    }
+}
+
+/// An entry which was taken out of the search section by [`UserSearchSection::rename`], along with all of it's children
+struct DetachedEntry {
+    entry: SearchListEntry,
+    is_directory: bool,
+    children: Vec<DetachedEntry>,
+}
+
+/// Collects the path of every file inside of a folder on the real filesystem, relative to the folder and using `/`
+/// as the separator. Symbolic links to folders are followed.
+///
+/// The entries of each folder are sorted, so the result is the same no matter what order the filesystem gives them in.
+///
+/// ### Arguments
+/// * `root` - The folder to collect the files of
+/// * `ignore` - The entries to skip, matched against their path relative to `root`
+pub(crate) fn collect_files(
+    root: &Utf8Path,
+    ignore: Option<&IgnoreRules>,
+) -> Result<Vec<Utf8PathBuf>, SearchError> {
+    fn collect(
+        root: &Utf8Path,
+        folder: &Utf8Path,
+        ignore: Option<&IgnoreRules>,
+        files: &mut Vec<Utf8PathBuf>,
+    ) -> Result<(), SearchError> {
+        let mut entries = folder.read_dir_utf8()?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by(|a, b| a.file_name().cmp(b.file_name()));
+
+        for entry in entries {
+            // the file type of the entry itself doesn't follow symbolic links, so get it from the path instead
+            let is_dir = entry.path().is_dir();
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .map_err(|_| SearchError::InvalidParent)?;
+
+            if let Some(rules) = ignore && rules.is_ignored(relative, is_dir) {
+                continue;
+            }
+
+            if is_dir {
+                collect(root, entry.path(), ignore, files)?;
+            } else {
+                files.push(relative.as_str().replace('\\', "/").into());
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut files = vec![];
+    collect(root, root, ignore, &mut files)?;
+    Ok(files)
+}
+
+/// Gets the path list entries of a folder and every folder above it, starting from the top. The root itself
+/// has no path list entry, so it is never included.
+fn folder_ancestors(
+    search: &impl SearchLookup,
+    folder: smash_arc::Hash40,
+) -> Result<Vec<&PathListEntry>, GenerateError> {
+    let mut ancestors = vec![];
+    let mut current = folder;
+    while current != smash_arc::Hash40::from("/") {
+        let entry = search.get_path_list_entry_from_hash(current)?;
+        if !entry.is_directory() {
+            return Err(GenerateError::InvalidFolder);
+        }
+        ancestors.push(entry);
+        current = entry.parent.hash40();
+    }
+
+    ancestors.reverse();
+    Ok(ancestors)
+}
+
 /// A user-path based search section which implements the data.arc's search section to the degree required by [`smash_arc::SearchLookup`]
 pub struct UserSearchSection {
     folder_lookup: Vec<smash_arc::HashToIndex>,
@@ -485,6 +715,10 @@ pub struct UserSearchSection {
     path_index_lookup: Vec<smash_arc::HashToIndex>,
     path_indices: Vec<u32>,
     paths: Vec<PathListEntry>,
+    /// The slot in the path list indices of every path list entry, so that moving an entry doesn't have to search for it
+    path_slots: Vec<u32>,
+    /// Slots which were invalidated by a removal, these are given to the next entries that get added
+    free_slots: Vec<usize>,
 }
 
 impl UserSearchSection {
@@ -494,7 +728,50 @@ impl UserSearchSection {
         let paths: Vec<&Utf8Path> = filesystem.lines().map(Utf8Path::new).collect();
         let result = Self::from_paths(&paths)?;
 
-        let path_labels = paths.into_iter().flat_map(|path| {
+        result.add_labels(&paths);
+
+        Ok(result)
+    }
+
+    /// Creates a search section out of every file inside of a folder on the real filesystem, such as an extracted
+    /// data.arc or a mod folder.
+    ///
+    /// The paths are relative to `root`, and just like [`from_path`](Self::from_path), components can be hex strings
+    /// (see [`path_to_hash`]). Folders without any files in them are not included.
+    ///
+    /// Every file in the game's filesystem has an extension and is inside of a folder, so files without an extension
+    /// (like `.git/HEAD`) and files directly inside of `root` (like `README.md` or `info.toml`) are skipped instead of
+    /// failing the whole search section.
+    ///
+    /// ### Arguments
+    /// * `root` - The folder to create the search section from
+    /// * `ignore` - The entries in the folder to skip, such as [`IgnoreRules::default`]
+    pub fn from_dir<P: AsRef<Utf8Path>>(
+        root: P,
+        ignore: Option<&IgnoreRules>,
+    ) -> Result<Self, SearchError> {
+        let files: Vec<Utf8PathBuf> = collect_files(root.as_ref(), ignore)?
+            .into_iter()
+            .filter(|path| {
+                path.extension().is_some()
+                    && path
+                        .parent()
+                        .is_some_and(|parent| !parent.as_str().is_empty())
+            })
+            .collect();
+
+        let paths: Vec<&Utf8Path> = files.iter().map(Utf8PathBuf::as_path).collect();
+        let result = Self::from_paths(&paths)?;
+
+        result.add_labels(&paths);
+
+        Ok(result)
+    }
+
+    /// Adds every component of the paths that the search section was created from to the global label map,
+    /// and then fills in the rest of the labels from the search section
+    fn add_labels(&self, paths: &[&Utf8Path]) {
+        let path_labels = paths.iter().flat_map(|path| {
             path.components()
                 .into_iter()
                 .filter_map(|component| {
@@ -522,281 +799,1816 @@ impl UserSearchSection {
         let labels = hash40::Hash40::label_map();
         let mut map = labels.lock().unwrap();
         map.add_labels(path_labels);
-        crate::generate::fill_label_map_from_search(&result, &mut map).unwrap();
-
-        Ok(result)
+        crate::generate::fill_label_map_from_search(self, &mut map).unwrap();
     }
 
     pub fn from_paths(path_list: &[&Utf8Path]) -> Result<Self, SearchError> {
-        struct Context {
-            folder_lookup: BTreeMap<smash_arc::Hash40, usize>,
-            path_lookup: BTreeMap<smash_arc::Hash40, usize>,
+        let mut builder = SectionBuilder::new();
+
+        for path in path_list {
+            let entry = make_path_entry(path)?;
+
+            // path lists can have the same file more than once
+            if builder.path_lookup.contains_key(&entry.path.hash40()) {
+                continue;
+            }
 
-            folders: Vec<FolderPathListEntry>,
-            paths: Vec<PathListEntry>,
+            builder.ensure_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
+            builder.link_entry(entry.0, false)?;
         }
 
-        impl Context {
-            fn make_path_entry(path: &Utf8Path) -> Result<PathListEntry, SearchError> {
-                let file_name = path.file_name().ok_or(SearchError::MissingFileName)?;
+        Ok(builder.finish())
+    }
+}
 
-                let (file_name, extension) = if file_name.starts_with("0x") {
-                    let mut split = file_name.split('.');
-                    (
-                        split.next().ok_or(SearchError::MissingFileName)?,
-                        split.next().ok_or(SearchError::MissingExtension)?,
-                    )
-                } else {
-                    let extension = path.extension().ok_or(SearchError::MissingExtension)?;
-                    (file_name, extension)
-                };
+/// The indices of an entry added with [`UserSearchSection::push_entry`]
+struct PushedEntry {
+    folder_index: Option<usize>,
+    slot: usize,
+}
+
+/// Builds a search section in bulk.
+///
+/// Inserting into the sorted lookups of a [`UserSearchSection`] one entry at a time is slow for large search sections,
+/// so the lookups are kept in `BTreeMap`s and only turned into the search section's lookups once everything is linked.
+/// The last child of every folder is also remembered, so linking an entry never has to walk the sibling chain.
+struct SectionBuilder {
+    section: UserSearchSection,
+    folder_lookup: BTreeMap<smash_arc::Hash40, usize>,
+    path_lookup: BTreeMap<smash_arc::Hash40, usize>,
+    last_children: Vec<usize>,
+}
+
+impl SectionBuilder {
+    fn new() -> Self {
+        // start it off with our lord and savior, the root path
+        let mut folder_lookup = BTreeMap::new();
+        folder_lookup.insert(smash_arc::Hash40::from("/"), 0);
+
+        Self {
+            section: UserSearchSection {
+                folder_lookup: vec![],
+                folders: vec![make_root_folder()],
+                path_index_lookup: vec![],
+                path_indices: vec![],
+                paths: vec![],
+                path_slots: vec![],
+                free_slots: vec![],
+            },
+            folder_lookup,
+            path_lookup: BTreeMap::new(),
+            last_children: vec![0xFF_FFFF],
+        }
+    }
+
+    /// The same as [`UserSearchSection::link_entry`]
+    fn link_entry(
+        &mut self,
+        entry: SearchListEntry,
+        is_directory: bool,
+    ) -> Result<(), SearchError> {
+        let parent_index = *self
+            .folder_lookup
+            .get(&entry.parent.hash40())
+            .ok_or(SearchError::InvalidParent)?;
+
+        if self.path_lookup.contains_key(&entry.path.hash40()) {
+            return Err(SearchError::AlreadyExists);
+        }
+
+        let pushed = self.section.push_entry(
+            parent_index,
+            entry,
+            is_directory,
+            Some(self.last_children[parent_index]),
+        );
+
+        self.last_children[parent_index] = pushed.slot;
+        self.path_lookup.insert(entry.path.hash40(), pushed.slot);
+
+        if let Some(folder_index) = pushed.folder_index {
+            self.folder_lookup.insert(entry.path.hash40(), folder_index);
+            self.last_children.push(0xFF_FFFF);
+        }
+
+        Ok(())
+    }
+
+    /// The same as [`UserSearchSection::ensure_folder`]
+    fn ensure_folder(&mut self, path: &Utf8Path) -> Result<(), SearchError> {
+        if path == Utf8Path::new("")
+            || self
+                .folder_lookup
+                .contains_key(&path_to_hash(path).to_smash_arc())
+        {
+            return Ok(());
+        }
+
+        self.ensure_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
+        self.link_entry(make_folder_entry(path)?.0, true)
+    }
+
+    fn finish(self) -> UserSearchSection {
+        fn to_lookup(lookup: BTreeMap<smash_arc::Hash40, usize>) -> Vec<smash_arc::HashToIndex> {
+            lookup
+                .into_iter()
+                .map(|(hash, index)| {
+                    let mut key = smash_arc::HashToIndex::default();
+                    key.set_hash(hash.crc32());
+                    key.set_length(hash.len());
+                    key.set_index(index as u32);
+                    key
+                })
+                .collect()
+        }
+
+        let Self {
+            mut section,
+            folder_lookup,
+            path_lookup,
+            ..
+        } = self;
+
+        section.folder_lookup = to_lookup(folder_lookup);
+        section.path_index_lookup = to_lookup(path_lookup);
+        section
+    }
+}
 
-                let parent = path.parent().ok_or(SearchError::InvalidParent)?;
+impl UserSearchSection {
+    /// Creates an empty search section, which only contains the root folder
+    pub fn new() -> Self {
+        let mut folder_lookup = vec![];
+        lookup_insert(&mut folder_lookup, smash_arc::Hash40::from("/"), 0);
 
-                let full_path = path_to_hash(path);
-                let name = Hash40::from_label(file_name).unwrap();
-                let extension = Hash40::from_label(extension).unwrap();
-                let parent_hash = path_to_hash(parent);
+        Self {
+            folder_lookup,
+            folders: vec![make_root_folder()],
+            path_index_lookup: vec![],
+            path_indices: vec![],
+            paths: vec![],
+            path_slots: vec![],
+            free_slots: vec![],
+        }
+    }
 
-                let mut path = smash_arc::HashToIndex::default();
-                let mut file_name = smash_arc::HashToIndex::default();
-                let mut parent = smash_arc::HashToIndex::default();
-                let mut ext = smash_arc::HashToIndex::default();
+    /// Gets the index of a path list entry from its hash. Just like the child and sibling indices, the path lookup
+    /// points to a slot in the path list indices instead of the path list entry itself.
+    fn find_path_index(&self, hash: smash_arc::Hash40) -> Option<usize> {
+        lookup_find(&self.path_index_lookup, hash).map(|slot| self.path_indices[slot] as usize)
+    }
 
-                path.set_hash(full_path.crc());
-                path.set_length(full_path.str_len());
-                path.set_index(0xFF_FFFFu32);
+    /// Rebuilds the slot of every path list entry and the free slots from the path list indices
+    fn rebuild_slots(&mut self) {
+        self.path_slots = vec![0xFF_FFFF; self.paths.len()];
+        self.free_slots.clear();
+        for (slot, index) in self.path_indices.iter().enumerate() {
+            if *index == 0xFF_FFFF {
+                self.free_slots.push(slot);
+            } else {
+                self.path_slots[*index as usize] = slot as u32;
+            }
+        }
+    }
 
-                file_name.set_hash(name.crc());
-                file_name.set_length(name.str_len());
+    fn child_hashes(&self, folder_index: usize) -> Vec<smash_arc::Hash40> {
+        let mut children = vec![];
+        let mut current_child = self.folders[folder_index].get_first_child_index();
+        while current_child != 0xFF_FFFF {
+            let path = &self.paths[self.path_indices[current_child] as usize];
+            children.push(path.path.hash40());
+            current_child = path.path.index() as usize;
+        }
+        children
+    }
 
-                parent.set_hash(parent_hash.crc());
-                parent.set_length(parent_hash.str_len());
+    /// Adds an entry to the tables and to the end of it's parent's children, without adding it to the lookups.
+    ///
+    /// `last_child` is the slot of the parent's last child, if it is already known. Otherwise the sibling chain is walked to find it.
+    fn push_entry(
+        &mut self,
+        parent_index: usize,
+        entry: SearchListEntry,
+        is_directory: bool,
+        last_child: Option<usize>,
+    ) -> PushedEntry {
+        let last_child = last_child.unwrap_or_else(|| {
+            let mut last_child = 0xFF_FFFF;
+            let mut current_child = self.folders[parent_index].get_first_child_index();
+            while current_child != 0xFF_FFFF {
+                last_child = current_child;
+                current_child = self.paths[self.path_indices[current_child] as usize]
+                    .path
+                    .index() as usize;
+            }
+            last_child
+        });
 
-                ext.set_hash(extension.crc());
-                ext.set_length(extension.str_len());
+        let (mut path_entry, folder_index) = if is_directory {
+            // folders need both a folder entry and a path list entry
+            let mut folder = FolderPathListEntry(entry);
+            folder.set_first_child_index(0xFF_FFFF);
+            self.folders.push(folder);
+            (folder.as_path_entry(), Some(self.folders.len() - 1))
+        } else {
+            (PathListEntry(entry), None)
+        };
 
-                Ok(PathListEntry(SearchListEntry {
-                    path,
-                    file_name,
-                    parent,
-                    ext,
-                }))
+        let path_index = self.paths.len();
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.path_indices[slot] = path_index as u32;
+                slot
+            }
+            None => {
+                self.path_indices.push(path_index as u32);
+                self.path_indices.len() - 1
             }
+        };
 
-            fn make_folder_entry(path: &Utf8Path) -> Result<FolderPathListEntry, SearchError> {
-                let file_name = path.file_name().ok_or(SearchError::MissingFileName)?;
-                let parent = path.parent().ok_or(SearchError::InvalidParent)?;
+        path_entry.path.set_index(0xFF_FFFF);
+        self.paths.push(path_entry);
+        self.path_slots.push(slot as u32);
 
-                let full_path = path_to_hash(path);
-                let name = Hash40::from_label(file_name).unwrap();
-                let parent_hash = if parent == Utf8Path::new("") {
-                    Hash40::new("/")
-                } else {
-                    path_to_hash(parent)
-                };
+        if last_child == 0xFF_FFFF {
+            self.folders[parent_index].set_first_child_index(slot as u32);
+        } else {
+            let sibling = self.path_indices[last_child] as usize;
+            self.paths[sibling].path.set_index(slot as u32);
+        }
+
+        PushedEntry { folder_index, slot }
+    }
+
+    /// Adds an entry to the end of it's parent's children, the parent must already exist
+    fn link_entry(
+        &mut self,
+        entry: SearchListEntry,
+        is_directory: bool,
+    ) -> Result<(), SearchError> {
+        let parent_index = lookup_find(&self.folder_lookup, entry.parent.hash40())
+            .ok_or(SearchError::InvalidParent)?;
+
+        if lookup_find(&self.path_index_lookup, entry.path.hash40()).is_some() {
+            return Err(SearchError::AlreadyExists);
+        }
+
+        let pushed = self.push_entry(parent_index, entry, is_directory, None);
+
+        lookup_insert(
+            &mut self.path_index_lookup,
+            entry.path.hash40(),
+            pushed.slot,
+        );
+
+        if let Some(folder_index) = pushed.folder_index {
+            lookup_insert(&mut self.folder_lookup, entry.path.hash40(), folder_index);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a folder and all of it's missing parents, doing nothing if the folder already exists
+    fn ensure_folder(&mut self, path: &Utf8Path) -> Result<(), SearchError> {
+        if path == Utf8Path::new("")
+            || lookup_find(&self.folder_lookup, path_to_hash(path).to_smash_arc()).is_some()
+        {
+            return Ok(());
+        }
+
+        self.ensure_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
+        self.link_entry(make_folder_entry(path)?.0, true)
+    }
 
-                let mut path = smash_arc::HashToIndex::default();
-                let mut file_name = smash_arc::HashToIndex::default();
-                let mut parent = smash_arc::HashToIndex::default();
-                let ext = smash_arc::HashToIndex::default();
+    /// Inserts a new file into the search section, creating any of the parent folders that don't already exist.
+    ///
+    /// Just like [`from_paths`](Self::from_paths), components can be hex strings (see [`path_to_hash`]).
+    pub fn insert_file<P: AsRef<Utf8Path>>(&mut self, path: P) -> Result<(), SearchError> {
+        let path = path.as_ref();
+        let entry = make_path_entry(path)?;
+        self.ensure_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
+        self.link_entry(entry.0, false)
+    }
+
+    /// Inserts a new folder into the search section, creating any of the parent folders that don't already exist.
+    ///
+    /// Just like [`from_paths`](Self::from_paths), components can be hex strings (see [`path_to_hash`]).
+    pub fn insert_folder<P: AsRef<Utf8Path>>(&mut self, path: P) -> Result<(), SearchError> {
+        let path = path.as_ref();
+        if lookup_find(&self.path_index_lookup, path_to_hash(path).to_smash_arc()).is_some() {
+            return Err(SearchError::AlreadyExists);
+        }
 
-                path.set_hash(full_path.crc());
-                path.set_length(full_path.str_len());
+        self.ensure_folder(path)
+    }
 
-                file_name.set_hash(name.crc());
-                file_name.set_length(name.str_len());
+    /// Removes a file or a folder (along with everything inside of it) from the search section.
+    ///
+    /// The siblings of the entry are relinked so that walking the parent folder skips over it.
+    pub fn remove(&mut self, path: impl ToSmashArc) -> Result<(), SearchError> {
+        let hash = path.to_smash_arc();
 
-                parent.set_hash(parent_hash.crc());
-                parent.set_length(parent_hash.str_len());
+        let slot = lookup_find(&self.path_index_lookup, hash).ok_or(SearchError::MissingPath)?;
 
-                Ok(FolderPathListEntry(SearchListEntry {
-                    path,
-                    file_name,
-                    parent,
-                    ext,
-                }))
+        if self.paths[self.path_indices[slot] as usize].is_directory() {
+            let folder_index = lookup_find(&self.folder_lookup, hash).unwrap();
+            for child in self.child_hashes(folder_index) {
+                self.remove(child)?;
             }
+        }
 
-            fn insert_folder(&mut self, path: &Utf8Path) -> Result<(), SearchError> {
-                let mut folder = Self::make_folder_entry(path)?;
-                let parent_index =
-                    if let Some(index) = self.folder_lookup.get(&folder.parent.hash40()) {
-                        *index
-                    } else {
-                        self.insert_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
-                        self.folder_lookup
-                            .get(&folder.parent.hash40())
-                            .copied()
-                            .unwrap()
-                    };
-
-                // insert the folder, but we aren't done yet, we need to create a path list entry
-                folder.set_first_child_index(0xFF_FFFF);
-                self.folder_lookup
-                    .insert(folder.path.hash40(), self.folders.len());
-                self.folders.push(folder);
-
-                let new_path_index = self.paths.len();
-                let mut path_entry = folder.as_path_entry();
-                path_entry.path.set_index(0xFF_FFFF);
-                self.paths.push(path_entry);
-                self.path_lookup
-                    .insert(path_entry.path.hash40(), new_path_index);
-
-                let first_child = self.folders[parent_index].get_first_child_index();
-
-                if first_child == 0xFF_FFFF {
-                    self.folders[parent_index].set_first_child_index(new_path_index as u32);
-                } else {
-                    let mut current_child = first_child;
-                    loop {
-                        if self.paths[current_child].path.index() == 0xFF_FFFF {
-                            self.paths[current_child]
-                                .path
-                                .set_index(new_path_index as u32);
-                            break;
-                        }
-
-                        current_child = self.paths[current_child].path.index() as usize;
-                    }
+        // removing the children moves entries around, but the slot of the entry always stays the same
+        let path_index = self.path_indices[slot] as usize;
+        let entry = self.paths[path_index];
+        let next = entry.path.index();
+
+        // point whatever came before the entry to whatever comes after it
+        let parent_index = lookup_find(&self.folder_lookup, entry.parent.hash40())
+            .ok_or(SearchError::InvalidParent)?;
+        let mut current_child = self.folders[parent_index].get_first_child_index();
+        if current_child == slot {
+            self.folders[parent_index].set_first_child_index(next);
+        } else {
+            while current_child != 0xFF_FFFF {
+                let sibling = self.path_indices[current_child] as usize;
+                if self.paths[sibling].path.index() as usize == slot {
+                    self.paths[sibling].path.set_index(next);
+                    break;
                 }
 
-                Ok(())
+                current_child = self.paths[sibling].path.index() as usize;
             }
+        }
 
-            fn insert_file(&mut self, path: &Utf8Path) -> Result<(), SearchError> {
-                let mut path_entry = Self::make_path_entry(path)?;
-                let parent_index =
-                    if let Some(index) = self.folder_lookup.get(&path_entry.parent.hash40()) {
-                        *index
-                    } else {
-                        self.insert_folder(path.parent().ok_or(SearchError::InvalidParent)?)?;
-                        self.folder_lookup
-                            .get(&path_entry.parent.hash40())
-                            .copied()
-                            .unwrap()
-                    };
-
-                let new_path_index = self.paths.len();
-                path_entry.path.set_index(0xFF_FFFF);
-                self.paths.push(path_entry);
-                self.path_lookup
-                    .insert(path_entry.path.hash40(), new_path_index);
-
-                let first_child = self.folders[parent_index].get_first_child_index();
-
-                if first_child == 0xFF_FFFF {
-                    self.folders[parent_index].set_first_child_index(new_path_index as u32);
-                } else {
-                    let mut current_child = first_child;
-                    loop {
-                        if self.paths[current_child].path.index() == 0xFF_FFFF {
-                            self.paths[current_child]
-                                .path
-                                .set_index(new_path_index as u32);
-                            break;
-                        }
-
-                        current_child = self.paths[current_child].path.index() as usize;
-                    }
-                }
-                Ok(())
+        // nothing points to the slot anymore, so it gets invalidated until it is reused. the entry itself is swapped
+        // with the last one so that the path list doesn't have any holes in it, and only the slot of the moved entry
+        // has to be repointed since everything else refers to the slot
+        self.path_indices[slot] = 0xFF_FFFF;
+        self.free_slots.push(slot);
+        lookup_remove(&mut self.path_index_lookup, hash);
+        self.paths.swap_remove(path_index);
+        self.path_slots.swap_remove(path_index);
+        if path_index < self.paths.len() {
+            let moved_slot = self.path_slots[path_index] as usize;
+            self.path_indices[moved_slot] = path_index as u32;
+        }
+
+        if entry.is_directory() {
+            let folder_index = lookup_find(&self.folder_lookup, hash).unwrap();
+            lookup_remove(&mut self.folder_lookup, hash);
+            self.folders.swap_remove(folder_index);
+            if folder_index < self.folders.len() {
+                lookup_insert(
+                    &mut self.folder_lookup,
+                    self.folders[folder_index].path.hash40(),
+                    folder_index,
+                );
             }
         }
 
-        let mut context = Context {
-            folder_lookup: BTreeMap::new(),
-            path_lookup: BTreeMap::new(),
-            folders: Vec::new(),
-            paths: Vec::new(),
-        };
+        Ok(())
+    }
 
-        let mut root_path = {
-            // start it off with our lord and savior, the root path
-            let mut path = smash_arc::HashToIndex::default();
-            let root_hash = Hash40::new("/");
-            path.set_hash(root_hash.crc());
-            path.set_length(root_hash.str_len());
-
-            FolderPathListEntry(SearchListEntry {
-                path,
-                file_name: path,
-                parent: smash_arc::HashToIndex::default(),
-                ext: smash_arc::HashToIndex::default(),
-            })
+    fn detach(&mut self, hash: smash_arc::Hash40) -> Result<DetachedEntry, SearchError> {
+        let path_index = self.find_path_index(hash).ok_or(SearchError::MissingPath)?;
+        let entry = self.paths[path_index];
+
+        let detached = if entry.is_directory() {
+            let folder_index = lookup_find(&self.folder_lookup, hash).unwrap();
+            let folder = self.folders[folder_index];
+            let children = self
+                .child_hashes(folder_index)
+                .into_iter()
+                .map(|child| self.detach(child))
+                .collect::<Result<_, _>>()?;
+
+            DetachedEntry {
+                entry: SearchListEntry {
+                    path: folder.path,
+                    file_name: folder.file_name,
+                    parent: folder.parent,
+                    ext: smash_arc::HashToIndex::default(),
+                },
+                is_directory: true,
+                children,
+            }
+        } else {
+            DetachedEntry {
+                entry: entry.0,
+                is_directory: false,
+                children: vec![],
+            }
         };
 
-        root_path.set_first_child_index(0xFF_FFFF);
+        self.remove(hash)?;
 
-        context.folders.push(root_path);
-        context
-            .folder_lookup
-            .insert(smash_arc::Hash40::from("/"), 0);
+        Ok(detached)
+    }
 
-        for path in path_list {
-            context.insert_file(path)?;
+    fn attach(&mut self, detached: DetachedEntry, parent: Hash40) -> Result<(), SearchError> {
+        let DetachedEntry {
+            mut entry,
+            is_directory,
+            children,
+        } = detached;
+
+        // the first component of a path is hashed as-is, so entries in the root can't be joined onto it
+        let name = entry.file_name.hash40().to_external();
+        let full_path = if parent == Hash40::new("/") {
+            name
+        } else {
+            parent.join_path(name)
+        };
+        entry.path = make_hash_to_index(full_path);
+        entry.parent = make_hash_to_index(parent);
+
+        self.link_entry(entry, is_directory)?;
+
+        for child in children {
+            self.attach(child, full_path)?;
         }
 
-        let Context {
-            folder_lookup,
-            path_lookup,
-            folders,
-            paths,
-        } = context;
+        Ok(())
+    }
 
-        let folder_lookup: Vec<smash_arc::HashToIndex> = folder_lookup
-            .into_iter()
-            .map(|(hash, index)| {
-                let mut key = smash_arc::HashToIndex::default();
-                key.set_hash(hash.crc32());
-                key.set_length(hash.len());
-                key.set_index(index as u32);
-                key
-            })
-            .collect();
+    /// Moves a file or a folder (along with everything inside of it) to a new path, creating any of the parent folders
+    /// that don't already exist. This can be used to rename an entry, to move it to a different folder, or both.
+    ///
+    /// The children of a folder keep their names and order. When renaming a file the extension is taken from the new path.
+    ///
+    /// ### Arguments
+    /// * `from` - The entry to move
+    /// * `to` - The new path of the entry, just like [`from_paths`](Self::from_paths), components can be hex strings (see [`path_to_hash`])
+    pub fn rename<P: AsRef<Utf8Path>>(
+        &mut self,
+        from: impl ToSmashArc,
+        to: P,
+    ) -> Result<(), SearchError> {
+        let from = from.to_smash_arc();
+        let to = to.as_ref();
+
+        let path_index = self.find_path_index(from).ok_or(SearchError::MissingPath)?;
+        let is_directory = self.paths[path_index].is_directory();
+
+        // build the new entry first so that a bad path doesn't leave the section half-modified
+        let entry = if is_directory {
+            make_folder_entry(to)?.0
+        } else {
+            make_path_entry(to)?.0
+        };
 
-        let path_index_lookup: Vec<smash_arc::HashToIndex> = path_lookup
-            .into_iter()
-            .map(|(hash, index)| {
-                let mut key = smash_arc::HashToIndex::default();
-                key.set_hash(hash.crc32());
-                key.set_length(hash.len());
-                key.set_index(index as u32);
-                key
-            })
-            .collect();
+        if lookup_find(&self.path_index_lookup, entry.path.hash40()).is_some() {
+            return Err(SearchError::AlreadyExists);
+        }
 
-        let path_indices = (0..paths.len()).into_iter().map(|x| x as u32).collect();
+        // moving a folder inside of itself would detach the new parent along with it
+        if to.ancestors().skip(1).any(|ancestor| {
+            !ancestor.as_str().is_empty() && path_to_hash(ancestor).to_smash_arc() == from
+        }) {
+            return Err(SearchError::InvalidParent);
+        }
 
-        Ok(Self {
-            folder_lookup,
-            folders,
-            path_index_lookup,
-            path_indices,
-            paths,
-        })
+        let mut detached = self.detach(from)?;
+        detached.entry.file_name = entry.file_name;
+        if !is_directory {
+            detached.entry.ext = entry.ext;
+        }
+
+        self.ensure_folder(to.parent().ok_or(SearchError::InvalidParent)?)?;
+        self.attach(detached, entry.parent.hash40().to_external())
     }
 }
 
-impl SearchLookup for UserSearchSection {
-    fn get_folder_path_to_index(&self) -> &[smash_arc::HashToIndex] {
-        &self.folder_lookup
-    }
+impl UserSearchSection {
+    /// Creates a standalone search section out of a folder in another search section, which is useful for creating
+    /// small but realistic search sections out of the data.arc.
+    ///
+    /// The folders leading up to `root` are included (without any of their other children) so that all of the
+    /// hashes stay the same.
+    ///
+    /// ```rs
+    /// let arc = ArcFile::open("D:/data.arc").unwrap();
+    /// let mario = UserSearchSection::from_search(&arc, "fighter/mario")?;
+    /// ```
+    ///
+    /// ### Arguments
+    /// * `search` - The search section to copy from
+    /// * `root` - The folder to copy (copying "/" will copy the entire search section)
+    pub fn from_search(
+        search: &impl SearchLookup,
+        root: impl ToSmashArc,
+    ) -> Result<Self, GenerateError> {
+        // the entries are rebuilt from their hashes since the indices are specific to the other search section
+        fn copy_entry(entry: &SearchListEntry, is_directory: bool) -> SearchListEntry {
+            SearchListEntry {
+                path: make_hash_to_index(entry.path.hash40().to_external()),
+                file_name: make_hash_to_index(entry.file_name.hash40().to_external()),
+                parent: make_hash_to_index(entry.parent.hash40().to_external()),
+                ext: if is_directory {
+                    smash_arc::HashToIndex::default()
+                } else {
+                    make_hash_to_index(entry.ext.hash40().to_external())
+                },
+            }
+        }
 
-    fn get_folder_path_list(&self) -> &[FolderPathListEntry] {
-        &self.folders
+        fn copy_children(
+            builder: &mut SectionBuilder,
+            search: &impl SearchLookup,
+            children: Vec<SearchEntry>,
+        ) -> Result<(), GenerateError> {
+            let paths = search.get_path_list();
+            for child in children {
+                match child {
+                    SearchEntry::File(index) => {
+                        builder.link_entry(copy_entry(&paths[index], false), false)?;
+                    }
+                    SearchEntry::Folder {
+                        path_index,
+                        children,
+                    } => {
+                        builder.link_entry(copy_entry(&paths[path_index], true), true)?;
+                        copy_children(builder, search, children)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        let root = root.to_smash_arc();
+        let mut builder = SectionBuilder::new();
+
+        for ancestor in folder_ancestors(search, root)? {
+            builder.link_entry(copy_entry(ancestor, true), true)?;
+        }
+
+        let children = walk_search_section(search, root, None)?;
+        copy_children(&mut builder, search, children)?;
+
+        Ok(builder.finish())
     }
 
-    fn get_path_to_index(&self) -> &[smash_arc::HashToIndex] {
-        &self.path_index_lookup
+    /// Creates a search section from a [`SearchTree`], such as one that was cached as JSON.
+    ///
+    /// The ancestors of the tree are inserted first, followed by every node in the tree.
+    pub fn from_tree(tree: &SearchTree) -> Result<Self, SearchError> {
+        fn link_nodes(
+            builder: &mut SectionBuilder,
+            nodes: &[SearchNode],
+        ) -> Result<(), SearchError> {
+            for node in nodes {
+                builder.link_entry(node.to_entry(), node.is_directory())?;
+                if let Some(children) = node.children.as_ref() {
+                    link_nodes(builder, children)?;
+                }
+            }
+            Ok(())
+        }
+
+        let mut builder = SectionBuilder::new();
+
+        for ancestor in tree.ancestors.iter() {
+            builder.link_entry(ancestor.to_entry(), true)?;
+        }
+
+        link_nodes(&mut builder, &tree.nodes)?;
+
+        Ok(builder.finish())
     }
+}
 
-    fn get_path_list_indices(&self) -> &[u32] {
-        &self.path_indices
+impl Default for UserSearchSection {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn get_path_list(&self) -> &[PathListEntry] {
-        &self.paths
+/// The magic at the start of every binary search section
+const BINARY_MAGIC: &[u8; 8] = b"ARCSRCH\0";
+
+/// The current version of the binary search section format, this is bumped whenever the layout changes
+const BINARY_VERSION: u32 = 1;
+
+// HashToIndex is a 64-bit bitfield of (hash: 32, length: 8, index: 24), packing it by hand keeps the
+// binary format the same as the in-memory layout without relying on the bitfield internals
+fn hash_to_index_to_bits(value: &smash_arc::HashToIndex) -> u64 {
+    let hash = value.hash40();
+    (hash.crc32() as u64) | ((hash.len() as u64) << 32) | ((value.index() as u64) << 40)
+}
+
+fn hash_to_index_from_bits(bits: u64) -> smash_arc::HashToIndex {
+    let mut value = smash_arc::HashToIndex::default();
+    value.set_hash(bits as u32);
+    value.set_length((bits >> 32) as u8);
+    value.set_index((bits >> 40) as u32);
+    value
+}
+
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], SearchError> {
+        if self.bytes.len() < count {
+            return Err(SearchError::InvalidBinary);
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SearchError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, SearchError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_hash_to_index(&mut self) -> Result<smash_arc::HashToIndex, SearchError> {
+        self.read_u64().map(hash_to_index_from_bits)
+    }
+
+    fn read_search_entry(&mut self) -> Result<SearchListEntry, SearchError> {
+        Ok(SearchListEntry {
+            path: self.read_hash_to_index()?,
+            file_name: self.read_hash_to_index()?,
+            parent: self.read_hash_to_index()?,
+            ext: self.read_hash_to_index()?,
+        })
+    }
+}
+
+fn write_search_entry(bytes: &mut Vec<u8>, entry: &SearchListEntry) {
+    for value in [&entry.path, &entry.file_name, &entry.parent, &entry.ext] {
+        bytes.extend_from_slice(&hash_to_index_to_bits(value).to_le_bytes());
+    }
+}
+
+impl UserSearchSection {
+    /// Serializes the tables of the search section into a versioned binary format.
+    ///
+    /// The format is a 32 byte header (magic, version, and the length of each table) followed by each table.
+    /// All of the tables except for the path indices, which come last, are made of little-endian 8 byte records
+    /// with the same layout as the data.arc.
+    ///
+    /// The label map is not included, labels have to be loaded separately.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            32 + (self.folder_lookup.len() + self.path_index_lookup.len()) * 8
+                + (self.folders.len() + self.paths.len()) * 32
+                + self.path_indices.len() * 4,
+        );
+
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.extend_from_slice(&BINARY_VERSION.to_le_bytes());
+        for count in [
+            self.folder_lookup.len(),
+            self.folders.len(),
+            self.path_index_lookup.len(),
+            self.paths.len(),
+            self.path_indices.len(),
+        ] {
+            bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        }
+
+        for lookup in self.folder_lookup.iter() {
+            bytes.extend_from_slice(&hash_to_index_to_bits(lookup).to_le_bytes());
+        }
+
+        for folder in self.folders.iter() {
+            write_search_entry(&mut bytes, &folder.0);
+        }
+
+        for lookup in self.path_index_lookup.iter() {
+            bytes.extend_from_slice(&hash_to_index_to_bits(lookup).to_le_bytes());
+        }
+
+        for path in self.paths.iter() {
+            write_search_entry(&mut bytes, &path.0);
+        }
+
+        for index in self.path_indices.iter() {
+            bytes.extend_from_slice(&index.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Deserializes a search section from the binary format written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SearchError> {
+        let mut reader = BinaryReader { bytes };
+
+        if reader.take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(SearchError::InvalidBinary);
+        }
+
+        let version = reader.read_u32()?;
+        if version != BINARY_VERSION {
+            return Err(SearchError::UnsupportedVersion(version));
+        }
+
+        let folder_lookup_count = reader.read_u32()? as usize;
+        let folder_count = reader.read_u32()? as usize;
+        let path_index_lookup_count = reader.read_u32()? as usize;
+        let path_count = reader.read_u32()? as usize;
+        let path_index_count = reader.read_u32()? as usize;
+
+        // check the size up front so that a corrupt header can't make us allocate a huge amount of memory
+        let expected = (folder_lookup_count + path_index_lookup_count) * 8
+            + (folder_count + path_count) * 32
+            + path_index_count * 4;
+        if reader.bytes.len() != expected {
+            return Err(SearchError::InvalidBinary);
+        }
+
+        let folder_lookup = (0..folder_lookup_count)
+            .map(|_| reader.read_hash_to_index())
+            .collect::<Result<_, _>>()?;
+
+        let folders = (0..folder_count)
+            .map(|_| reader.read_search_entry().map(FolderPathListEntry))
+            .collect::<Result<_, _>>()?;
+
+        let path_index_lookup = (0..path_index_lookup_count)
+            .map(|_| reader.read_hash_to_index())
+            .collect::<Result<_, _>>()?;
+
+        let paths = (0..path_count)
+            .map(|_| reader.read_search_entry().map(PathListEntry))
+            .collect::<Result<_, _>>()?;
+
+        let path_indices = (0..path_index_count)
+            .map(|_| reader.read_u32())
+            .collect::<Result<_, _>>()?;
+
+        let mut section = Self {
+            folder_lookup,
+            folders,
+            path_index_lookup,
+            path_indices,
+            paths,
+            path_slots: vec![],
+            free_slots: vec![],
+        };
+
+        section.check_indices()?;
+        section.rebuild_slots();
+
+        Ok(section)
+    }
+
+    /// Checks that every index in the tables points inside of the table it is for, so that a corrupt file is
+    /// rejected when it is loaded instead of panicking when it is walked
+    fn check_indices(&self) -> Result<(), SearchError> {
+        // the child, sibling, and path indices use this as the end of the list
+        fn check(index: usize, len: usize) -> Result<(), SearchError> {
+            if index == 0xFF_FFFF || index < len {
+                Ok(())
+            } else {
+                Err(SearchError::InvalidBinary)
+            }
+        }
+
+        for lookup in self.folder_lookup.iter() {
+            if lookup.index() as usize >= self.folders.len() {
+                return Err(SearchError::InvalidBinary);
+            }
+        }
+
+        // the path lookup points to slots, which can't be ones that were invalidated by a removal
+        for lookup in self.path_index_lookup.iter() {
+            match self.path_indices.get(lookup.index() as usize) {
+                Some(index) if *index != 0xFF_FFFF => {}
+                _ => return Err(SearchError::InvalidBinary),
+            }
+        }
+
+        for folder in self.folders.iter() {
+            check(folder.get_first_child_index(), self.path_indices.len())?;
+        }
+
+        for path in self.paths.iter() {
+            check(path.path.index() as usize, self.path_indices.len())?;
+        }
+
+        // every path list entry has to be in exactly one slot
+        let mut referenced = vec![false; self.paths.len()];
+        for index in self.path_indices.iter() {
+            check(*index as usize, self.paths.len())?;
+            if *index != 0xFF_FFFF && std::mem::replace(&mut referenced[*index as usize], true) {
+                return Err(SearchError::InvalidBinary);
+            }
+        }
+
+        if referenced.contains(&false) {
+            return Err(SearchError::InvalidBinary);
+        }
+
+        Ok(())
+    }
+
+    /// Helper method to write the binary format from [`to_bytes`](Self::to_bytes) to a file
+    pub fn to_file_binary<P: AsRef<Path>>(&self, path: P) -> Result<(), SearchError> {
+        std::fs::write(path, self.to_bytes()).map_err(SearchError::from)
+    }
+
+    /// Helper method to read the binary format from [`to_bytes`](Self::to_bytes) from a file
+    ///
+    /// Unlike [`from_path`](Self::from_path), this does not add anything to the label map.
+    pub fn from_file_binary<P: AsRef<Path>>(path: P) -> Result<Self, SearchError> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl SearchLookup for UserSearchSection {
+    fn get_folder_path_to_index(&self) -> &[smash_arc::HashToIndex] {
+        &self.folder_lookup
+    }
+
+    fn get_folder_path_list(&self) -> &[FolderPathListEntry] {
+        &self.folders
+    }
+
+    fn get_path_to_index(&self) -> &[smash_arc::HashToIndex] {
+        &self.path_index_lookup
+    }
+
+    fn get_path_list_indices(&self) -> &[u32] {
+        &self.path_indices
+    }
+
+    fn get_path_list(&self) -> &[PathListEntry] {
+        &self.paths
+    }
+}
+
+/// Gets the label of a single path component, falling back to a hex string if the label is unknown or
+/// cannot be used as a single component
+fn component_label(labels: &LabelMap, hash: Hash40) -> String {
+    match labels.label_of(hash) {
+        Some(label) if !label.is_empty() && !label.contains('/') && !label.starts_with("0x") => {
+            label
+        }
+        _ => format!("0x{:010x}", hash.0),
+    }
+}
+
+fn folder_path_label(
+    search: &impl SearchLookup,
+    folder: smash_arc::Hash40,
+    labels: &LabelMap,
+    cache: &mut HashMap<smash_arc::Hash40, Option<String>>,
+) -> Option<String> {
+    if let Some(path) = cache.get(&folder) {
+        return path.clone();
+    }
+
+    let path = search
+        .get_path_list_entry_from_hash(folder)
+        .ok()
+        .and_then(|entry| {
+            let name = component_label(labels, entry.file_name.hash40().to_external());
+            if entry.parent.hash40() == smash_arc::Hash40::from("/") {
+                Some(name)
+            } else {
+                folder_path_label(search, entry.parent.hash40(), labels, cache)
+                    .map(|parent| format!("{}/{}", parent, name))
+            }
+        });
+
+    cache.insert(folder, path.clone());
+    path
+}
+
+/// Converts every file in a search section into a newline-separated path list in the format accepted by
+/// [`UserSearchSection::from_path`], which is useful for taking a snapshot of the data.arc's search section.
+///
+/// Components use their label when one is known, otherwise they are written as hex strings. File names which are written
+/// as hex strings are followed by their extension, for example `0x0c3fc6fb4e.nutexb`. The paths are sorted so that the
+/// output is stable.
+///
+/// ### Arguments
+/// * `search` - The search section to convert
+/// * `labels` - The label map to label the components with
+pub fn to_path_list(search: &impl SearchLookup, labels: &LabelMap) -> String {
+    let mut cache = HashMap::new();
+
+    let mut lines: Vec<String> = search
+        .get_path_list()
+        .iter()
+        .filter(|path| !path.is_directory())
+        .map(|path| {
+            let name_hash = path.file_name.hash40().to_external();
+            let extension_hash = path.ext.hash40().to_external();
+
+            // the extension of a labeled file name has to match the real one, otherwise the file name
+            // has to be written as a hash with the extension after it
+            let name = match labels.label_of(name_hash) {
+                Some(name)
+                    if !name.contains('/')
+                        && !name.starts_with("0x")
+                        && name
+                            .rsplit_once('.')
+                            .map(|(_, extension)| Hash40::new(extension) == extension_hash)
+                            .unwrap_or(false) =>
+                {
+                    name
+                }
+                _ => format!(
+                    "0x{:010x}.{}",
+                    name_hash.0,
+                    component_label(labels, extension_hash)
+                ),
+            };
+
+            // if the parent can't be reconstructed from components then the full hash of the parent still works,
+            // since the first component of a path is hashed as-is
+            let parent = folder_path_label(search, path.parent.hash40(), labels, &mut cache)
+                .unwrap_or_else(|| format!("0x{:010x}", path.parent.hash40().to_external().0));
+
+            format!("{}/{}", parent, name)
+        })
+        .collect();
+
+    lines.sort();
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// Helper method to write the path list from [`to_path_list`] to a file
+pub fn write_path_list<P: AsRef<Path>>(
+    search: &impl SearchLookup,
+    labels: &LabelMap,
+    path: P,
+) -> std::io::Result<()> {
+    std::fs::write(path, to_path_list(search, labels))
+}
+
+/// An owned entry of a search section, which unlike [`SearchEntry`] does not depend on the search section it came from.
+///
+/// Nodes are serialized as maps, with `children` being `null` for files:
+/// ```json
+/// {
+///     "path": "fighter/mario/model/body/c00",
+///     "file_name": "c00",
+///     "parent": "fighter/mario/model/body",
+///     "extension": null,
+///     "label": "fighter/mario/model/body/c00",
+///     "children": [ ... ]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchNode {
+    /// The full path of the entry
+    pub path: Hash40,
+    /// The name of the entry, which includes the extension for files
+    pub file_name: Hash40,
+    /// The full path of the parent folder
+    pub parent: Hash40,
+    /// The extension of the entry, or `None` for folders
+    #[serde(default)]
+    pub extension: Option<Hash40>,
+    /// The label of the full path, if it was known when the node was created
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The children of the entry, or `None` for files
+    #[serde(default)]
+    pub children: Option<Vec<SearchNode>>,
+}
+
+impl SearchNode {
+    fn from_path_entry(entry: &PathListEntry, labels: &LabelMap) -> Self {
+        let path = entry.path.hash40().to_external();
+        Self {
+            path,
+            file_name: entry.file_name.hash40().to_external(),
+            parent: entry.parent.hash40().to_external(),
+            extension: if entry.is_directory() {
+                None
+            } else {
+                Some(entry.ext.hash40().to_external())
+            },
+            label: labels.label_of(path),
+            children: if entry.is_directory() {
+                Some(vec![])
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Creates the nodes for the entries returned by [`walk_search_section`]
+    ///
+    /// ### Arguments
+    /// * `search` - The search section that was walked
+    /// * `entries` - The entries to convert
+    /// * `labels` - The label map to resolve the labels of the nodes with
+    pub fn from_entries(
+        search: &impl SearchLookup,
+        entries: &[SearchEntry],
+        labels: &LabelMap,
+    ) -> Vec<Self> {
+        let paths = search.get_path_list();
+        entries
+            .iter()
+            .map(|entry| match entry {
+                SearchEntry::File(index) => Self::from_path_entry(&paths[*index], labels),
+                SearchEntry::Folder {
+                    path_index,
+                    children,
+                } => Self {
+                    children: Some(Self::from_entries(search, children, labels)),
+                    ..Self::from_path_entry(&paths[*path_index], labels)
+                },
+            })
+            .collect()
+    }
+
+    /// Checks if the node is a folder
+    pub fn is_directory(&self) -> bool {
+        self.children.is_some()
+    }
+
+    fn to_entry(&self) -> SearchListEntry {
+        SearchListEntry {
+            path: make_hash_to_index(self.path),
+            file_name: make_hash_to_index(self.file_name),
+            parent: make_hash_to_index(self.parent),
+            ext: match self.extension {
+                Some(extension) if !self.is_directory() => make_hash_to_index(extension),
+                _ => smash_arc::HashToIndex::default(),
+            },
+        }
+    }
+}
+
+/// An owned, label-resolved copy of a folder in a search section, which can be cached and turned back into a
+/// [`UserSearchSection`] with [`UserSearchSection::from_tree`].
+///
+/// The ancestors are the folders leading up to the walked folder, starting from the top, and are stored without
+/// their children so that the tree can be rebuilt on its own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchTree {
+    /// The folders above the walked folder, starting from the top
+    pub ancestors: Vec<SearchNode>,
+    /// The contents of the walked folder
+    pub nodes: Vec<SearchNode>,
+}
+
+impl SearchTree {
+    /// Walks a folder of a search section and resolves every entry into a [`SearchNode`]
+    ///
+    /// ### Arguments
+    /// * `search` - The search section to walk
+    /// * `root` - The folder to walk (walking "/" will walk the entire search section)
+    /// * `depth` - How deep to walk, see [`walk_search_section`]
+    /// * `labels` - The label map to resolve the labels of the nodes with
+    pub fn from_search(
+        search: &impl SearchLookup,
+        root: impl ToSmashArc,
+        depth: Option<usize>,
+        labels: &LabelMap,
+    ) -> Result<Self, GenerateError> {
+        let root = root.to_smash_arc();
+
+        let ancestors = folder_ancestors(search, root)?
+            .into_iter()
+            .map(|entry| SearchNode::from_path_entry(entry, labels))
+            .collect();
+
+        let entries = walk_search_section(search, root, depth)?;
+
+        Ok(Self {
+            ancestors,
+            nodes: SearchNode::from_entries(search, &entries, labels),
+        })
+    }
+}
+
+/// A file which exists in both search sections, but in a different folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MovedPath {
+    /// The path of the file in the old search section
+    pub from: Hash40,
+    /// The path of the file in the new search section
+    pub to: Hash40,
+}
+
+/// The difference between the files of two search sections, created with [`diff`]
+///
+/// The difference is serialized as three lists of paths:
+/// ```json
+/// {
+///     "added": ["fighter/mario/model/body/c08/model.numdlb"],
+///     "removed": ["fighter/mario/model/body/c07/model.numdlb"],
+///     "moved": [{ "from": "fighter/mario/old/model.numdlb", "to": "fighter/mario/new/model.numdlb" }]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchDiff {
+    /// The files which only exist in the new search section
+    pub added: Vec<Hash40>,
+    /// The files which only exist in the old search section
+    pub removed: Vec<Hash40>,
+    /// The files which were moved to a different folder
+    pub moved: Vec<MovedPath>,
+}
+
+impl SearchDiff {
+    /// Checks if there are no differences at all
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+
+    /// Gets the new path of a file from the old search section, if it was moved
+    pub fn moved_to(&self, path: Hash40) -> Option<Hash40> {
+        self.moved
+            .iter()
+            .find(|moved| moved.from == path)
+            .map(|moved| moved.to)
+    }
+
+    /// Gets the vanilla files in a configuration's `share_to_vanilla` which no longer exist in the new search section
+    pub fn stale_share_to_vanilla(&self, config: &Config) -> Vec<Hash40> {
+        let gone: HashSet<Hash40> = self
+            .removed
+            .iter()
+            .copied()
+            .chain(self.moved.iter().map(|moved| moved.from))
+            .collect();
+
+        let mut stale: Vec<Hash40> = config
+            .share_to_vanilla
+            .keys()
+            .filter(|path| gone.contains(*path))
+            .copied()
+            .collect();
+
+        stale.sort_by_key(|path| path.0);
+        stale
+    }
+
+    /// Formats the difference as plain text, using labels wherever they are known:
+    /// ```text
+    /// + fighter/mario/model/body/c08/model.numdlb
+    /// - fighter/mario/model/body/c07/model.numdlb
+    /// > fighter/mario/old/model.numdlb -> fighter/mario/new/model.numdlb
+    /// ```
+    pub fn to_text(&self, labels: &LabelMap) -> String {
+        let mut text = String::new();
+
+        for path in self.added.iter() {
+            let _ = writeln!(text, "+ {}", label_or_hex(labels, *path));
+        }
+
+        for path in self.removed.iter() {
+            let _ = writeln!(text, "- {}", label_or_hex(labels, *path));
+        }
+
+        for moved in self.moved.iter() {
+            let _ = writeln!(
+                text,
+                "> {} -> {}",
+                label_or_hex(labels, moved.from),
+                label_or_hex(labels, moved.to)
+            );
+        }
+
+        text
+    }
+}
+
+/// Compares the files of two search sections, such as the data.arc of two different game versions.
+///
+/// Files are matched by their full path hash. Files that were removed from one folder and added to another
+/// with the same file name are reported as moved, as long as there is only one file with that name on either side.
+///
+/// ### Arguments
+/// * `a` - The old search section
+/// * `b` - The new search section
+pub fn diff(a: &impl SearchLookup, b: &impl SearchLookup) -> SearchDiff {
+    fn files(search: &impl SearchLookup) -> HashMap<Hash40, (Hash40, Hash40)> {
+        search
+            .get_path_list()
+            .iter()
+            .filter(|path| !path.is_directory())
+            .map(|path| {
+                (
+                    path.path.hash40().to_external(),
+                    (
+                        path.file_name.hash40().to_external(),
+                        path.ext.hash40().to_external(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    let old = files(a);
+    let new = files(b);
+
+    // group everything that changed by file name so that moves can be found
+    let mut removed_by_name: HashMap<(Hash40, Hash40), Vec<Hash40>> = HashMap::new();
+    for (path, name) in old.iter() {
+        if !new.contains_key(path) {
+            removed_by_name.entry(*name).or_default().push(*path);
+        }
+    }
+
+    let mut added_by_name: HashMap<(Hash40, Hash40), Vec<Hash40>> = HashMap::new();
+    for (path, name) in new.iter() {
+        if !old.contains_key(path) {
+            added_by_name.entry(*name).or_default().push(*path);
+        }
+    }
+
+    let mut diff = SearchDiff::default();
+
+    for (name, removed) in removed_by_name {
+        match added_by_name.remove(&name) {
+            Some(added) if added.len() == 1 && removed.len() == 1 => diff.moved.push(MovedPath {
+                from: removed[0],
+                to: added[0],
+            }),
+            Some(added) => {
+                diff.removed.extend(removed);
+                diff.added.extend(added);
+            }
+            None => diff.removed.extend(removed),
+        }
+    }
+
+    for (_, added) in added_by_name {
+        diff.added.extend(added);
+    }
+
+    // hash map iteration order is random, so sort everything to keep the output stable
+    diff.added.sort_by_key(|path| path.0);
+    diff.removed.sort_by_key(|path| path.0);
+    diff.moved.sort_by_key(|moved| moved.from.0);
+
+    diff
+}
+
+/// The number of folders kept in [`SearchStats::largest_folders`]
+const LARGEST_FOLDER_COUNT: usize = 10;
+
+/// A folder and how many direct children it has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FolderSize {
+    /// The path of the folder
+    pub path: Hash40,
+    /// The number of files and folders directly inside of the folder
+    pub children: usize,
+}
+
+/// Statistics on the contents of a search section, created with [`stats`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchStats {
+    /// The number of files
+    pub files: usize,
+    /// The number of folders, not including the root
+    pub folders: usize,
+    /// The number of files with each extension
+    pub extensions: HashMap<Hash40, usize>,
+    /// The number of files inside of each top-level folder, such as `fighter` or `stage`
+    pub top_level: HashMap<Hash40, usize>,
+    /// How deep the deepest file or folder is, where the entries in the root have a depth of `1`
+    pub max_depth: usize,
+    /// The folders with the most direct children, largest first
+    pub largest_folders: Vec<FolderSize>,
+    /// The number of files and folders which have a label for their full path
+    pub labeled: usize,
+}
+
+impl SearchStats {
+    /// The share of files and folders which have a label for their full path, from `0.0` to `1.0`
+    pub fn labeled_share(&self) -> f64 {
+        let total = self.files + self.folders;
+        if total == 0 {
+            0.0
+        } else {
+            self.labeled as f64 / total as f64
+        }
+    }
+}
+
+struct StatsVisitor<'a> {
+    labels: &'a LabelMap,
+    stats: SearchStats,
+    top_level: Option<Hash40>,
+    /// The folders being visited and how many children they have so far, starting with the root
+    open_folders: Vec<(Hash40, usize)>,
+    sizes: Vec<FolderSize>,
+}
+
+impl StatsVisitor<'_> {
+    fn visit_entry(&mut self, entry: &PathListEntry, depth: usize) {
+        let path = entry.path.hash40().to_external();
+
+        if self.labels.label_of(path).is_some() {
+            self.stats.labeled += 1;
+        }
+
+        self.stats.max_depth = self.stats.max_depth.max(depth + 1);
+
+        if let Some((_, children)) = self.open_folders.last_mut() {
+            *children += 1;
+        }
+    }
+}
+
+impl SearchVisitor for StatsVisitor<'_> {
+    fn enter_folder(&mut self, _: usize, entry: &PathListEntry, depth: usize) -> VisitFlow {
+        self.visit_entry(entry, depth);
+        self.stats.folders += 1;
+
+        let path = entry.path.hash40().to_external();
+        if depth == 0 {
+            self.top_level = Some(path);
+        }
+
+        self.open_folders.push((path, 0));
+        VisitFlow::Continue
+    }
+
+    fn visit_file(&mut self, _: usize, entry: &PathListEntry, depth: usize) -> VisitFlow {
+        self.visit_entry(entry, depth);
+        self.stats.files += 1;
+
+        *self
+            .stats
+            .extensions
+            .entry(entry.ext.hash40().to_external())
+            .or_default() += 1;
+
+        // files directly in the root don't belong to a top-level folder
+        if depth > 0 && let Some(top_level) = self.top_level {
+            *self.stats.top_level.entry(top_level).or_default() += 1;
+        }
+
+        VisitFlow::Continue
+    }
+
+    fn leave_folder(&mut self, _: usize, _: &PathListEntry, _: usize) -> VisitFlow {
+        if let Some((path, children)) = self.open_folders.pop() {
+            self.sizes.push(FolderSize { path, children });
+        }
+        VisitFlow::Continue
+    }
+}
+
+/// Collects statistics on a search section, using the global label map to count the labeled paths.
+///
+/// This is useful for sanity checking a [`UserSearchSection`] against the data.arc, or for tracking how much of
+/// the search section is labeled.
+///
+/// ### Arguments
+/// * `search` - The search section to collect the statistics of
+pub fn stats(search: &impl SearchLookup) -> Result<SearchStats, GenerateError> {
+    let labels = Hash40::label_map();
+    let labels = labels.lock().unwrap();
+    stats_with_labels(search, &labels)
+}
+
+/// The same as [`stats`], except that the labeled paths are counted with the provided label map
+///
+/// ### Arguments
+/// * `search` - The search section to collect the statistics of
+/// * `labels` - The label map to count the labeled paths with
+pub fn stats_with_labels(
+    search: &impl SearchLookup,
+    labels: &LabelMap,
+) -> Result<SearchStats, GenerateError> {
+    let mut visitor = StatsVisitor {
+        labels,
+        stats: SearchStats::default(),
+        top_level: None,
+        open_folders: vec![(Hash40::new("/"), 0)],
+        sizes: vec![],
+    };
+
+    walk_search_section_with(search, "/", &mut visitor)?;
+
+    let StatsVisitor {
+        mut stats,
+        open_folders,
+        mut sizes,
+        ..
+    } = visitor;
+
+    // the root is never left, so it is still open after the walk
+    sizes.extend(
+        open_folders
+            .into_iter()
+            .map(|(path, children)| FolderSize { path, children }),
+    );
+
+    sizes.sort_by(|a, b| b.children.cmp(&a.children).then(a.path.0.cmp(&b.path.0)));
+    sizes.truncate(LARGEST_FOLDER_COUNT);
+    stats.largest_folders = sizes;
+
+    Ok(stats)
+}
+
+/// An inverted index of a search section, for finding every path with a file name or extension.
+///
+/// [`SearchLookup`] can only look up entries by their full path, so this is useful when only the name of a file
+/// is known, like `def_mario_001_col.nutexb`, but not which folder it is in.
+///
+/// ### Example
+/// ```rs
+/// let arc = ArcFile::open("D:/data.arc").unwrap();
+/// let index = NameIndex::new(&arc);
+/// for path in index.by_file_name("def_mario_001_col.nutexb") {
+///     println!("{}", path);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NameIndex {
+    by_file_name: HashMap<Hash40, Vec<Hash40>>,
+    by_extension: HashMap<Hash40, Vec<Hash40>>,
+}
+
+impl NameIndex {
+    /// Builds the index from every entry of the search section's path list
+    pub fn new(search: &impl SearchLookup) -> Self {
+        let mut index = Self::default();
+
+        for path in search.get_path_list().iter() {
+            let full_path = path.path.hash40().to_external();
+
+            index
+                .by_file_name
+                .entry(path.file_name.hash40().to_external())
+                .or_default()
+                .push(full_path);
+
+            // folders don't have an extension
+            if !path.is_directory() {
+                index
+                    .by_extension
+                    .entry(path.ext.hash40().to_external())
+                    .or_default()
+                    .push(full_path);
+            }
+        }
+
+        // the path list is not sorted by anything useful, so sort the paths to keep the results stable
+        for paths in index
+            .by_file_name
+            .values_mut()
+            .chain(index.by_extension.values_mut())
+        {
+            paths.sort_by_key(|path| path.0);
+        }
+
+        index
+    }
+
+    /// Gets the full path of every file and folder with the name. The names of files include their extension.
+    pub fn by_file_name(&self, name: impl ToExternal) -> &[Hash40] {
+        self.by_file_name
+            .get(&name.to_external())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Gets the full path of every file with the extension, which should not include the leading `.`
+    pub fn by_extension(&self, extension: impl ToExternal) -> &[Hash40] {
+        self.by_extension
+            .get(&extension.to_external())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(path: &str) -> smash_arc::Hash40 {
+        path_to_hash(Utf8Path::new(path)).to_smash_arc()
+    }
+
+    fn section(paths: &[&str]) -> UserSearchSection {
+        let paths: Vec<&Utf8Path> = paths.iter().map(|path| Utf8Path::new(*path)).collect();
+        UserSearchSection::from_paths(&paths).unwrap()
+    }
+
+    /// Every entry inside of a folder, in the order they are walked
+    fn walk(search: &UserSearchSection, folder: &str) -> Vec<smash_arc::Hash40> {
+        fn collect(
+            search: &UserSearchSection,
+            entries: Vec<SearchEntry>,
+            out: &mut Vec<smash_arc::Hash40>,
+        ) {
+            let paths = search.get_path_list();
+            for entry in entries {
+                match entry {
+                    SearchEntry::File(index) => out.push(paths[index].path.hash40()),
+                    SearchEntry::Folder {
+                        path_index,
+                        children,
+                    } => {
+                        out.push(paths[path_index].path.hash40());
+                        collect(search, children, out);
+                    }
+                }
+            }
+        }
+
+        let mut out = vec![];
+        collect(
+            search,
+            walk_search_section(search, folder, None).unwrap(),
+            &mut out,
+        );
+        out
+    }
+
+    fn hashes(paths: &[&str]) -> Vec<smash_arc::Hash40> {
+        paths.iter().map(|path| hash(path)).collect()
+    }
+
+    /// Checks that every entry can still be found through the lookups after the tables were modified
+    fn assert_lookups(search: &UserSearchSection) {
+        for path in search.get_path_list() {
+            let found = search
+                .get_path_list_entry_from_hash(path.path.hash40())
+                .unwrap();
+            assert_eq!(found.path.hash40(), path.path.hash40());
+        }
+    }
+
+    #[test]
+    fn insert_matches_from_paths() {
+        let paths = ["a/b/x.txt", "a/b/y.txt", "a/z.txt", "c/w.txt"];
+        let built = section(&paths);
+
+        let mut inserted = UserSearchSection::new();
+        for path in paths {
+            inserted.insert_file(path).unwrap();
+        }
+
+        assert_eq!(walk(&built, "/"), walk(&inserted, "/"));
+        assert!(matches!(
+            inserted.insert_file("a/z.txt"),
+            Err(SearchError::AlreadyExists)
+        ));
+    }
+
+    #[test]
+    fn from_paths_skips_duplicates() {
+        let search = section(&["a/x.txt", "a/x.txt", "a/y.txt"]);
+        assert_eq!(walk(&search, "a"), hashes(&["a/x.txt", "a/y.txt"]));
+    }
+
+    #[test]
+    fn from_dir_skips_files_outside_of_folders() {
+        let root = Utf8PathBuf::try_from(std::env::temp_dir())
+            .unwrap()
+            .join(format!("arc-config-from-dir-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("fighter/mario")).unwrap();
+        std::fs::write(root.join("info.toml"), "").unwrap();
+        std::fs::write(root.join("fighter/mario/NOTES"), "").unwrap();
+        std::fs::write(root.join("fighter/mario/x.nutexb"), "").unwrap();
+
+        let search = UserSearchSection::from_dir(&root, None);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            walk(&search.unwrap(), "/"),
+            hashes(&["fighter", "fighter/mario", "fighter/mario/x.nutexb"])
+        );
+    }
+
+    #[test]
+    fn remove_relinks_siblings() {
+        let mut search = section(&["a/x.txt", "a/y.txt", "a/z.txt", "a/w.txt"]);
+
+        // middle of the chain
+        search.remove("a/y.txt").unwrap();
+        assert_eq!(
+            walk(&search, "a"),
+            hashes(&["a/x.txt", "a/z.txt", "a/w.txt"])
+        );
+
+        // first child of the folder
+        search.remove("a/x.txt").unwrap();
+        assert_eq!(walk(&search, "a"), hashes(&["a/z.txt", "a/w.txt"]));
+
+        // end of the chain
+        search.remove("a/w.txt").unwrap();
+        assert_eq!(walk(&search, "a"), hashes(&["a/z.txt"]));
+
+        assert_lookups(&search);
+        assert!(matches!(
+            search.remove("a/y.txt"),
+            Err(SearchError::MissingPath)
+        ));
+    }
+
+    #[test]
+    fn remove_fixes_up_swapped_entry() {
+        let mut search = section(&["a/x.txt", "b/y.txt", "b/z.txt"]);
+
+        // removing an entry from the start of the path list swaps the last entry into its place
+        search.remove("a/x.txt").unwrap();
+        assert_eq!(search.get_path_list().len(), 4);
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "/"),
+            hashes(&["a", "b", "b/y.txt", "b/z.txt"])
+        );
+
+        // the moved entry can still be removed and relinked correctly
+        search.remove("b/z.txt").unwrap();
+        assert_lookups(&search);
+        assert_eq!(walk(&search, "/"), hashes(&["a", "b", "b/y.txt"]));
+
+        // the section can keep growing after the removals
+        search.insert_file("b/v.txt").unwrap();
+        assert_lookups(&search);
+        assert_eq!(walk(&search, "b"), hashes(&["b/y.txt", "b/v.txt"]));
+    }
+
+    #[test]
+    fn remove_folder_removes_children() {
+        let mut search = section(&["a/b/x.txt", "a/b/y.txt", "a/z.txt", "c/w.txt"]);
+
+        search.remove("a/b").unwrap();
+
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "/"),
+            hashes(&["a", "a/z.txt", "c", "c/w.txt"])
+        );
+        assert!(search.get_folder_path_entry_from_hash(hash("a/b")).is_err());
+        assert!(search
+            .get_path_list_entry_from_hash(hash("a/b/x.txt"))
+            .is_err());
+    }
+
+    #[test]
+    fn rename_moves_subtree() {
+        let mut search = section(&["a/b/x.txt", "a/b/y.txt", "c/z.txt"]);
+
+        search.rename("a/b", "c/d").unwrap();
+
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "/"),
+            hashes(&["a", "c", "c/z.txt", "c/d", "c/d/x.txt", "c/d/y.txt"])
+        );
+        assert!(search
+            .get_path_list_entry_from_hash(hash("a/b/x.txt"))
+            .is_err());
+    }
+
+    #[test]
+    fn rename_file_into_new_folder() {
+        let mut search = section(&["a/x.txt", "a/y.txt"]);
+
+        search.rename("a/x.txt", "b/c/x.nutexb").unwrap();
+
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "/"),
+            hashes(&["a", "a/y.txt", "b", "b/c", "b/c/x.nutexb"])
+        );
+
+        let entry = search
+            .get_path_list_entry_from_hash(hash("b/c/x.nutexb"))
+            .unwrap();
+        assert_eq!(entry.ext.hash40(), smash_arc::Hash40::from("nutexb"));
+    }
+
+    #[test]
+    fn rename_to_root() {
+        let mut search = section(&["a/b/x.txt", "a/y.txt"]);
+
+        search.rename("a/b", "e").unwrap();
+
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "/"),
+            hashes(&["a", "a/y.txt", "e", "e/x.txt"])
+        );
+        assert!(search.get_folder_path_entry_from_hash(hash("e")).is_ok());
+    }
+
+    #[test]
+    fn rename_into_itself_fails() {
+        let mut search = section(&["a/b/x.txt"]);
+
+        assert!(matches!(
+            search.rename("a", "a/b/a"),
+            Err(SearchError::InvalidParent)
+        ));
+        assert_eq!(walk(&search, "/"), hashes(&["a", "a/b", "a/b/x.txt"]));
+    }
+
+    #[test]
+    fn rename_reuses_slots() {
+        let mut search = section(&["a/b/x.txt", "a/b/y.txt", "a/z.txt"]);
+        let slots = search.get_path_list_indices().len();
+
+        search.rename("a/b", "c").unwrap();
+        search.rename("c", "a/b").unwrap();
+
+        assert_eq!(search.get_path_list_indices().len(), slots);
+        assert_lookups(&search);
+        assert_eq!(
+            walk(&search, "a"),
+            hashes(&["a/z.txt", "a/b", "a/b/x.txt", "a/b/y.txt"])
+        );
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut search = section(&["a/b/x.txt", "a/b/y.txt", "a/z.txt", "c/w.txt"]);
+        // removing leaves an invalidated slot behind, which has to survive the round trip as well
+        search.remove("a/b/x.txt").unwrap();
+
+        let bytes = search.to_bytes();
+        let read = UserSearchSection::from_bytes(&bytes).unwrap();
+
+        assert_eq!(read.to_bytes(), bytes);
+        assert_eq!(walk(&read, "/"), walk(&search, "/"));
+        assert_lookups(&read);
+    }
+
+    #[test]
+    fn binary_rejects_lookup_to_removed_slot() {
+        let mut search = section(&["a/x.txt", "a/y.txt", "b/z.txt"]);
+        search.remove("a/x.txt").unwrap();
+        search.remove("b").unwrap();
+
+        let bytes = search.to_bytes();
+        let read = UserSearchSection::from_bytes(&bytes).unwrap();
+        assert_lookups(&read);
+        assert_eq!(walk(&read, "/"), hashes(&["a", "a/y.txt"]));
+
+        // point the first path lookup at one of the slots that the removals invalidated
+        let removed = search
+            .get_path_list_indices()
+            .iter()
+            .position(|index| *index == 0xFF_FFFF)
+            .unwrap();
+        let offset = 32
+            + search.get_folder_path_to_index().len() * 8
+            + search.get_folder_path_list().len() * 32;
+        let bits = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let bits = (bits & 0xFF_FFFF_FFFF) | ((removed as u64) << 40);
+
+        let mut bad = bytes;
+        bad[offset..offset + 8].copy_from_slice(&bits.to_le_bytes());
+        assert!(matches!(
+            UserSearchSection::from_bytes(&bad),
+            Err(SearchError::InvalidBinary)
+        ));
+    }
+
+    #[test]
+    fn binary_rejects_bad_header() {
+        let bytes = section(&["a/x.txt"]).to_bytes();
+
+        assert!(matches!(
+            UserSearchSection::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SearchError::InvalidBinary)
+        ));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            UserSearchSection::from_bytes(&bad_magic),
+            Err(SearchError::InvalidBinary)
+        ));
+
+        let mut bad_version = bytes;
+        bad_version[8..12].copy_from_slice(&(BINARY_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            UserSearchSection::from_bytes(&bad_version),
+            Err(SearchError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn binary_rejects_out_of_range_indices() {
+        let mut bytes = section(&["a/x.txt", "a/y.txt"]).to_bytes();
+
+        // the path indices are the last table, so point the last one past the end of the path list
+        let len = bytes.len();
+        bytes[len - 4..].copy_from_slice(&0x10u32.to_le_bytes());
+
+        assert!(matches!(
+            UserSearchSection::from_bytes(&bytes),
+            Err(SearchError::InvalidBinary)
+        ));
     }
 }
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use hash40::Hash40;
+use serde::Serialize;
+use smash_arc::SearchLookup;
+
+use crate::{search, Config, ToSmashArc};
+
+/// The field of a [`Config`] that an entry comes from, serialized with the same name as the field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigField {
+    UnshareBlacklist,
+    PreprocessReshare,
+    ShareToVanilla,
+    ShareToAdded,
+}
+
+/// A problem with an entry in a [`Config`]
+///
+/// Issues are serialized as a map tagged with their `kind`:
+/// ```json
+/// { "kind": "missing_file", "field": "share_to_vanilla", "path": "fighter/mario/model/body/c00/model.numdlb" }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationIssue {
+    /// The entry refers to a vanilla file which does not exist in the search section
+    MissingFile { field: ConfigField, path: Hash40 },
+
+    /// The entry refers to a vanilla file, but the path is a folder in the search section
+    NotAFile { field: ConfigField, path: Hash40 },
+}
+
+impl ValidationIssue {
+    /// The field of the configuration that the issue was found in
+    pub fn field(&self) -> ConfigField {
+        match self {
+            Self::MissingFile { field, .. } | Self::NotAFile { field, .. } => *field,
+        }
+    }
+
+    /// The path that the issue was found with
+    pub fn path(&self) -> Hash40 {
+        match self {
+            Self::MissingFile { path, .. } | Self::NotAFile { path, .. } => *path,
+        }
+    }
+}
+
+fn check_file(
+    search: &impl SearchLookup,
+    field: ConfigField,
+    path: Hash40,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match search.get_path_list_entry_from_hash(path.to_smash_arc()) {
+        Ok(entry) if entry.is_directory() => issues.push(ValidationIssue::NotAFile { field, path }),
+        Ok(_) => {}
+        Err(_) => issues.push(ValidationIssue::MissingFile { field, path }),
+    }
+}
+
+/// Checks that every vanilla file a configuration refers to exists in the search section.
+///
+/// The following entries are checked:
+/// * Every file in `unshare_blacklist`
+/// * Both files of every pair in `preprocess_reshare`
+/// * The vanilla file that every entry in `share_to_vanilla` and `share_to_added` shares from
+///
+/// The files being shared to are not checked, since they are allowed to be new files.
+///
+/// ### Arguments
+/// * `search` - The search section
+/// * `config` - The configuration to check
+pub fn validate(search: &impl SearchLookup, config: &Config) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+
+    for path in config.unshare_blacklist.iter() {
+        check_file(search, ConfigField::UnshareBlacklist, *path, &mut issues);
+    }
+
+    for (from, to) in config.preprocess_reshare.iter() {
+        check_file(search, ConfigField::PreprocessReshare, *from, &mut issues);
+        check_file(search, ConfigField::PreprocessReshare, *to, &mut issues);
+    }
+
+    for path in config.share_to_vanilla.keys() {
+        check_file(search, ConfigField::ShareToVanilla, *path, &mut issues);
+    }
+
+    for path in config.share_to_added.keys() {
+        check_file(search, ConfigField::ShareToAdded, *path, &mut issues);
+    }
+
+    // hash map iteration order is random, so sort everything to keep the output stable
+    issues.sort_by_key(|issue| (issue.field() as u8, issue.path().0));
+    issues.dedup();
+    issues
+}
+
+/// An entry in a configuration which was valid for the old search section, but is not for the new one
+///
+/// The entry is serialized as the [`ValidationIssue`] and the proposed fix, which is `null` if there is none:
+/// ```json
+/// {
+///     "issue": { "kind": "missing_file", "field": "share_to_vanilla", "path": "fighter/mario/old/model.numdlb" },
+///     "fix": "fighter/mario/new/model.numdlb"
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StaleEntry {
+    /// The issue with the entry in the new search section
+    pub issue: ValidationIssue,
+    /// The new path of the file, if it looks like it was only moved to a different folder
+    pub fix: Option<Hash40>,
+}
+
+/// Finds the entries in a configuration which became invalid between two versions of the search section, such as
+/// after a game update.
+///
+/// When a file only moved folders, which is decided the same way as the moved files of [`search::diff`], its new
+/// path is proposed as a fix which can be applied with [`apply_stale_fixes`].
+///
+/// ### Arguments
+/// * `old` - The search section the configuration was made for
+/// * `new` - The search section to check the configuration against
+/// * `config` - The configuration to check
+pub fn find_stale_entries(
+    old: &impl SearchLookup,
+    new: &impl SearchLookup,
+    config: &Config,
+) -> Vec<StaleEntry> {
+    let old_issues = validate(old, config);
+    let new_issues = validate(new, config);
+
+    let diff = search::diff(old, new);
+
+    new_issues
+        .into_iter()
+        .filter(|issue| !old_issues.contains(issue))
+        .map(|issue| StaleEntry {
+            issue,
+            fix: diff.moved_to(issue.path()),
+        })
+        .collect()
+}
+
+/// Applies the fixes proposed by [`find_stale_entries`] to a configuration, replacing every use of the old path
+/// in the field the issue was found in.
+///
+/// ### Returns
+/// The number of fixes that changed the configuration
+pub fn apply_stale_fixes(config: &mut Config, stale: &[StaleEntry]) -> usize {
+    fn rekey<V>(
+        map: &mut HashMap<Hash40, V>,
+        from: Hash40,
+        to: Hash40,
+        merge: impl FnOnce(&mut V, V),
+    ) -> bool {
+        let Some(value) = map.remove(&from) else {
+            return false;
+        };
+
+        if let Some(existing) = map.get_mut(&to) {
+            merge(existing, value);
+        } else {
+            map.insert(to, value);
+        }
+
+        true
+    }
+
+    fn replace<'a>(paths: impl Iterator<Item = &'a mut Hash40>, from: Hash40, to: Hash40) -> bool {
+        let mut changed = false;
+        for path in paths {
+            if *path == from {
+                *path = to;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    let mut applied = 0;
+
+    for entry in stale {
+        let Some(fix) = entry.fix else {
+            continue;
+        };
+
+        let from = entry.issue.path();
+
+        let changed = match entry.issue.field() {
+            ConfigField::UnshareBlacklist => {
+                replace(config.unshare_blacklist.iter_mut(), from, fix)
+            }
+            ConfigField::PreprocessReshare => {
+                let rekeyed = rekey(&mut config.preprocess_reshare, from, fix, |_, _| {});
+                let replaced = replace(config.preprocess_reshare.values_mut(), from, fix);
+                rekeyed || replaced
+            }
+            ConfigField::ShareToVanilla => {
+                rekey(&mut config.share_to_vanilla, from, fix, |existing, set| {
+                    existing.0.extend(set.0)
+                })
+            }
+            ConfigField::ShareToAdded => {
+                rekey(&mut config.share_to_added, from, fix, |existing, set| {
+                    existing.0.extend(set.0)
+                })
+            }
+        };
+
+        if changed {
+            applied += 1;
+        }
+    }
+
+    applied
+}